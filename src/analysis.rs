@@ -0,0 +1,8 @@
+pub mod control_flow;
+pub mod jack_os;
+pub mod lint;
+pub mod parsed_class;
+pub mod program;
+pub mod semantic_checker;
+pub mod semantic_error;
+pub mod type_checker;
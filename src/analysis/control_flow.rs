@@ -0,0 +1,196 @@
+use crate::analysis::parsed_class::ParsedClass;
+use crate::ast::{KeywordConstant, Statement, SubroutineDec, SubroutineKind, TermKind};
+use crate::diagnostic::{Diagnostic, Severity};
+
+/// Reports subroutines that can end without `return`, returns that do not match the return type
+/// and statements that can never run.
+pub fn check(classes: &[ParsedClass]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for parsed in classes {
+        for subroutine_dec in &parsed.class.subroutine_decs {
+            let mut checker = ControlFlowChecker {
+                parsed,
+                subroutine: subroutine_dec,
+                diagnostics: &mut diagnostics,
+            };
+            checker.check();
+        }
+    }
+    diagnostics
+}
+
+struct ControlFlowChecker<'a> {
+    parsed: &'a ParsedClass,
+    subroutine: &'a SubroutineDec,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl ControlFlowChecker<'_> {
+    fn check(&mut self) {
+        if !self.check_statements(&self.subroutine.body.statements) {
+            let name = &self.subroutine.name;
+            let message = format!("subroutine `{}` can end without `return`", name.name);
+            let mut diagnostic = self.parsed.diagnostic(Severity::Error, message, name.span);
+            diagnostic.note =
+                Some("every path of a Jack subroutine must end with `return`".to_string());
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Checks `statements` and returns whether they never fall through to what follows them.
+    fn check_statements(&mut self, statements: &[Statement]) -> bool {
+        let mut ends = false;
+        let mut reported = false;
+        for statement in statements {
+            // the rest of the block is still checked, but reported as unreachable once
+            if ends && !reported {
+                let message = "unreachable statement".to_string();
+                let diagnostic =
+                    self.parsed
+                        .diagnostic(Severity::Warning, message, statement.span());
+                self.diagnostics.push(diagnostic);
+                reported = true;
+            }
+            ends |= self.check_statement(statement);
+        }
+        ends
+    }
+
+    fn check_statement(&mut self, statement: &Statement) -> bool {
+        match statement {
+            Statement::Let(_) | Statement::Do(_) => false,
+            Statement::If(statement) => {
+                let then_ends = self.check_statements(&statement.statements);
+                match &statement.else_statements {
+                    Some(else_statements) => self.check_statements(else_statements) && then_ends,
+                    None => false,
+                }
+            }
+            Statement::While(statement) => {
+                self.check_statements(&statement.statements);
+                // Jack has no `break`, so `while (true)` only ends by returning
+                statement.condition.ops.is_empty()
+                    && statement.condition.term.kind
+                        == TermKind::KeywordConstant(KeywordConstant::True)
+            }
+            Statement::Return(statement) => {
+                let subroutine = self.subroutine;
+                // what a constructor returns is checked by the semantic checker
+                if subroutine.kind != SubroutineKind::Constructor {
+                    let message = match (&subroutine.return_type, &statement.value) {
+                        (None, Some(_)) => Some(format!(
+                            "void subroutine `{}` cannot return a value",
+                            subroutine.name.name
+                        )),
+                        (Some(return_type), None) => Some(format!(
+                            "subroutine `{}` must return a value of type `{}`",
+                            subroutine.name.name,
+                            return_type.name()
+                        )),
+                        _ => None,
+                    };
+                    if let Some(message) = message {
+                        let diagnostic =
+                            self.parsed
+                                .diagnostic(Severity::Error, message, statement.span);
+                        self.diagnostics.push(diagnostic);
+                    }
+                }
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::analysis::control_flow::check;
+    use crate::analysis::parsed_class::ParsedClass;
+    use crate::tokenizer::jack_tokenizer::JackTokenizer;
+
+    fn messages(src: &str) -> Vec<String> {
+        let tokenizer =
+            JackTokenizer::from_source(src, Some(Path::new("Main.jack")), Default::default())
+                .unwrap();
+        let parsed = ParsedClass::parse(tokenizer).unwrap();
+        check(&[parsed])
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn every_path_ending_with_return_is_accepted() {
+        let src = "\
+class Main {
+    function int sign(int x) {
+        if (x < 0) { return -1; } else {
+            if (x > 0) { return 1; } else { return 0; }
+        }
+    }
+    function void loop() {
+        while (true) { do Sys.wait(1); }
+    }
+}
+";
+
+        assert_eq!(Vec::<String>::new(), messages(src));
+    }
+
+    #[test]
+    fn reports_missing_returns_and_mismatched_values() {
+        let src = "\
+class Main {
+    function int sign(int x) {
+        if (x < 0) { return -1; }
+        while (x > 0) { return 1; }
+    }
+    function void main() {
+        return 1;
+    }
+    function int zero() {
+        return;
+    }
+    function void empty() {}
+}
+";
+
+        assert_eq!(
+            vec![
+                "Main.jack:2:18: error: subroutine `sign` can end without `return`",
+                "Main.jack:7:9: error: void subroutine `main` cannot return a value",
+                "Main.jack:10:9: error: subroutine `zero` must return a value of type `int`",
+                "Main.jack:12:19: error: subroutine `empty` can end without `return`",
+            ],
+            messages(src)
+        );
+    }
+
+    #[test]
+    fn reports_statements_after_return_once_per_block() {
+        let src = "\
+class Main {
+    function void main() {
+        var int x;
+        if (x) {
+            return;
+            let x = 1;
+            let x = 2;
+        }
+        return;
+        do Output.println();
+    }
+}
+";
+
+        assert_eq!(
+            vec![
+                "Main.jack:6:13: warning: unreachable statement",
+                "Main.jack:10:9: warning: unreachable statement",
+            ],
+            messages(src)
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use crate::analysis::program::{ClassSignature, SubroutineSignature};
+use crate::ast::SubroutineKind;
+use crate::ast::SubroutineKind::{Constructor, Function, Method};
+
+/// The classes of the Jack OS, which every program may use without their sources.
+pub const OS_CLASSES: [&str; 8] = [
+    "Math", "String", "Array", "Output", "Screen", "Keyboard", "Memory", "Sys",
+];
+
+type Subroutine = (
+    SubroutineKind,
+    &'static str,
+    &'static str,
+    &'static [&'static str],
+);
+
+/// The API of the Jack OS as `(kind, return type, name, parameter types)` per class.
+const OS_API: [(&str, &[Subroutine]); 8] = [
+    (
+        "Math",
+        &[
+            (Function, "void", "init", &[]),
+            (Function, "int", "abs", &["int"]),
+            (Function, "int", "multiply", &["int", "int"]),
+            (Function, "int", "divide", &["int", "int"]),
+            (Function, "int", "min", &["int", "int"]),
+            (Function, "int", "max", &["int", "int"]),
+            (Function, "int", "sqrt", &["int"]),
+        ],
+    ),
+    (
+        "String",
+        &[
+            (Constructor, "String", "new", &["int"]),
+            (Method, "void", "dispose", &[]),
+            (Method, "int", "length", &[]),
+            (Method, "char", "charAt", &["int"]),
+            (Method, "void", "setCharAt", &["int", "char"]),
+            (Method, "String", "appendChar", &["char"]),
+            (Method, "void", "eraseLastChar", &[]),
+            (Method, "int", "intValue", &[]),
+            (Method, "void", "setInt", &["int"]),
+            (Function, "char", "backSpace", &[]),
+            (Function, "char", "doubleQuote", &[]),
+            (Function, "char", "newLine", &[]),
+        ],
+    ),
+    (
+        "Array",
+        &[
+            (Function, "Array", "new", &["int"]),
+            (Method, "void", "dispose", &[]),
+        ],
+    ),
+    (
+        "Output",
+        &[
+            (Function, "void", "init", &[]),
+            (Function, "void", "moveCursor", &["int", "int"]),
+            (Function, "void", "printChar", &["char"]),
+            (Function, "void", "printString", &["String"]),
+            (Function, "void", "printInt", &["int"]),
+            (Function, "void", "println", &[]),
+            (Function, "void", "backSpace", &[]),
+        ],
+    ),
+    (
+        "Screen",
+        &[
+            (Function, "void", "init", &[]),
+            (Function, "void", "clearScreen", &[]),
+            (Function, "void", "setColor", &["boolean"]),
+            (Function, "void", "drawPixel", &["int", "int"]),
+            (Function, "void", "drawLine", &["int", "int", "int", "int"]),
+            (
+                Function,
+                "void",
+                "drawRectangle",
+                &["int", "int", "int", "int"],
+            ),
+            (Function, "void", "drawCircle", &["int", "int", "int"]),
+        ],
+    ),
+    (
+        "Keyboard",
+        &[
+            (Function, "void", "init", &[]),
+            (Function, "char", "keyPressed", &[]),
+            (Function, "char", "readChar", &[]),
+            (Function, "String", "readLine", &["String"]),
+            (Function, "int", "readInt", &["String"]),
+        ],
+    ),
+    (
+        "Memory",
+        &[
+            (Function, "void", "init", &[]),
+            (Function, "int", "peek", &["int"]),
+            (Function, "void", "poke", &["int", "int"]),
+            (Function, "Array", "alloc", &["int"]),
+            (Function, "void", "deAlloc", &["Array"]),
+        ],
+    ),
+    (
+        "Sys",
+        &[
+            (Function, "void", "init", &[]),
+            (Function, "void", "halt", &[]),
+            (Function, "void", "error", &["int"]),
+            (Function, "void", "wait", &["int"]),
+        ],
+    ),
+];
+
+/// The signature of the Jack OS class `name`, if there is one.
+pub fn os_class(name: &str) -> Option<ClassSignature> {
+    let (_, subroutines) = OS_API.iter().find(|(class, _)| *class == name)?;
+    let subroutines: HashMap<String, SubroutineSignature> = subroutines
+        .iter()
+        .map(|(kind, return_type, name, parameter_types)| {
+            let signature = SubroutineSignature {
+                kind: *kind,
+                return_type: (*return_type != "void").then(|| return_type.to_string()),
+                parameter_types: parameter_types.iter().map(|t| t.to_string()).collect(),
+            };
+            (name.to_string(), signature)
+        })
+        .collect();
+    Some(ClassSignature {
+        subroutines,
+        builtin: true,
+        complete: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::jack_os::{os_class, OS_CLASSES};
+    use crate::ast::SubroutineKind;
+
+    #[test]
+    fn every_os_class_has_a_signature() {
+        for name in OS_CLASSES {
+            let class = os_class(name).unwrap();
+            assert!(class.builtin);
+            assert!(!class.subroutines.is_empty(), "{}", name);
+        }
+        assert_eq!(None, os_class("Main"));
+    }
+
+    #[test]
+    fn signatures_describe_kind_and_types() {
+        let string = os_class("String").unwrap();
+        let append_char = &string.subroutines["appendChar"];
+        assert_eq!(SubroutineKind::Method, append_char.kind);
+        assert_eq!(Some("String".to_string()), append_char.return_type);
+        assert_eq!(vec!["char".to_string()], append_char.parameter_types);
+
+        let memory = os_class("Memory").unwrap();
+        let de_alloc = &memory.subroutines["deAlloc"];
+        assert_eq!(SubroutineKind::Function, de_alloc.kind);
+        assert_eq!(None, de_alloc.return_type);
+        assert_eq!(vec!["Array".to_string()], de_alloc.parameter_types);
+    }
+}
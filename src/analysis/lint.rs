@@ -0,0 +1,490 @@
+use std::collections::HashSet;
+
+use crate::analysis::parsed_class::ParsedClass;
+use crate::ast::{
+    ClassVarKind, Expression, Identifier, Statement, SubroutineCall, SubroutineDec, Term, TermKind,
+};
+use crate::diagnostic::{Diagnostic, Severity};
+
+/// A check for code that compiles but is probably a mistake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// Local variables that are never read.
+    UnusedVariable,
+    /// Parameters that are never read.
+    UnusedParameter,
+    /// Fields that are never read.
+    UnusedField,
+    /// Static variables that are never read.
+    UnusedStatic,
+    /// Parameters and local variables named like a field or static variable of their class.
+    Shadowing,
+    /// Assignments to a parameter or local variable that are overwritten or never read.
+    UnreadAssignment,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::UnusedVariable,
+        Lint::UnusedParameter,
+        Lint::UnusedField,
+        Lint::UnusedStatic,
+        Lint::Shadowing,
+        Lint::UnreadAssignment,
+    ];
+
+    /// The name used on the command line and as the code of its diagnostics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::UnusedField => "unused-field",
+            Lint::UnusedStatic => "unused-static",
+            Lint::Shadowing => "shadowing",
+            Lint::UnreadAssignment => "unread-assignment",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+/// Reports the enabled `lints` for each of `classes` as warnings, in source order.
+pub fn check(classes: &[ParsedClass], lints: &[Lint]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for parsed in classes {
+        let mut checker = LintChecker {
+            parsed,
+            lints,
+            class_vars: Vec::new(),
+            subroutine_vars: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+        checker.check();
+        checker
+            .diagnostics
+            .sort_by_key(|diagnostic| diagnostic.span.start);
+        diagnostics.extend(checker.diagnostics);
+    }
+    diagnostics
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VariableKind {
+    Static,
+    Field,
+    Parameter,
+    Local,
+}
+
+impl VariableKind {
+    fn description(&self) -> &'static str {
+        match self {
+            VariableKind::Static => "static variable",
+            VariableKind::Field => "field",
+            VariableKind::Parameter => "parameter",
+            VariableKind::Local => "variable",
+        }
+    }
+
+    fn unused_lint(&self) -> Lint {
+        match self {
+            VariableKind::Static => Lint::UnusedStatic,
+            VariableKind::Field => Lint::UnusedField,
+            VariableKind::Parameter => Lint::UnusedParameter,
+            VariableKind::Local => Lint::UnusedVariable,
+        }
+    }
+}
+
+struct Variable<'a> {
+    name: &'a Identifier,
+    kind: VariableKind,
+    read: bool,
+}
+
+struct LintChecker<'a> {
+    parsed: &'a ParsedClass,
+    lints: &'a [Lint],
+    class_vars: Vec<Variable<'a>>,
+    subroutine_vars: Vec<Variable<'a>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> LintChecker<'a> {
+    fn check(&mut self) {
+        let class = &self.parsed.class;
+        for class_var_dec in &class.class_var_decs {
+            let kind = match class_var_dec.kind {
+                ClassVarKind::Static => VariableKind::Static,
+                ClassVarKind::Field => VariableKind::Field,
+            };
+            for name in &class_var_dec.names {
+                self.class_vars.push(Variable {
+                    name,
+                    kind,
+                    read: false,
+                });
+            }
+        }
+        for subroutine_dec in &class.subroutine_decs {
+            self.check_subroutine_dec(subroutine_dec);
+        }
+        let class_vars = std::mem::take(&mut self.class_vars);
+        self.report_unused(&class_vars);
+    }
+
+    fn check_subroutine_dec(&mut self, subroutine_dec: &'a SubroutineDec) {
+        let parameters = subroutine_dec
+            .parameters
+            .iter()
+            .map(|parameter| (&parameter.name, VariableKind::Parameter));
+        let locals = subroutine_dec
+            .body
+            .var_decs
+            .iter()
+            .flat_map(|var_dec| var_dec.names.iter())
+            .map(|name| (name, VariableKind::Local));
+        self.subroutine_vars = parameters
+            .chain(locals)
+            .map(|(name, kind)| Variable {
+                name,
+                kind,
+                read: false,
+            })
+            .collect();
+        self.check_shadowing();
+
+        let mut reads = Vec::new();
+        statements_reads(&subroutine_dec.body.statements, &mut reads);
+        for name in reads {
+            self.mark_read(&name.name);
+        }
+
+        let subroutine_vars = std::mem::take(&mut self.subroutine_vars);
+        self.report_unused(&subroutine_vars);
+        if self.enabled(Lint::UnreadAssignment) {
+            // variables that are never read were reported as unused
+            let tracked: HashSet<&str> = subroutine_vars
+                .iter()
+                .filter(|variable| variable.read)
+                .map(|variable| variable.name.name.as_str())
+                .collect();
+            let mut liveness = Liveness {
+                checker: self,
+                tracked,
+            };
+            liveness.live_before(&subroutine_dec.body.statements, HashSet::new(), true);
+        }
+    }
+
+    fn check_shadowing(&mut self) {
+        if !self.enabled(Lint::Shadowing) {
+            return;
+        }
+        for variable in &self.subroutine_vars {
+            let shadowed = self
+                .class_vars
+                .iter()
+                .find(|class_var| class_var.name.name == variable.name.name);
+            if let Some(shadowed) = shadowed {
+                let message = format!(
+                    "{} `{}` shadows {} `{}`",
+                    variable.kind.description(),
+                    variable.name.name,
+                    shadowed.kind.description(),
+                    shadowed.name.name
+                );
+                let mut diagnostic = self.diagnostic(Lint::Shadowing, message, variable.name);
+                diagnostic.note = Some(format!(
+                    "`{}` is declared at line {}",
+                    shadowed.name.name, shadowed.name.span.line
+                ));
+                self.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    /// Marks the variable `name` resolves to as read. Names of classes resolve to nothing.
+    fn mark_read(&mut self, name: &str) {
+        let variable = self
+            .subroutine_vars
+            .iter_mut()
+            .chain(self.class_vars.iter_mut())
+            .find(|variable| variable.name.name == name);
+        if let Some(variable) = variable {
+            variable.read = true;
+        }
+    }
+
+    fn report_unused(&mut self, variables: &[Variable]) {
+        for variable in variables.iter().filter(|variable| !variable.read) {
+            let lint = variable.kind.unused_lint();
+            if self.enabled(lint) {
+                let message = format!(
+                    "unused {} `{}`",
+                    variable.kind.description(),
+                    variable.name.name
+                );
+                let diagnostic = self.diagnostic(lint, message, variable.name);
+                self.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    fn enabled(&self, lint: Lint) -> bool {
+        self.lints.contains(&lint)
+    }
+
+    fn diagnostic(&self, lint: Lint, message: String, name: &Identifier) -> Diagnostic {
+        let mut diagnostic = self
+            .parsed
+            .diagnostic(Severity::Warning, message, name.span);
+        diagnostic.code = Some(lint.name());
+        diagnostic
+    }
+}
+
+/// A backward pass that finds the variables whose current value may still be read.
+struct Liveness<'c, 'a> {
+    checker: &'c mut LintChecker<'a>,
+    tracked: HashSet<&'a str>,
+}
+
+impl<'a> Liveness<'_, 'a> {
+    /// Returns the variables live before `statements` given those live after them. Only
+    /// reports unread assignments when `report` is set.
+    fn live_before(
+        &mut self,
+        statements: &'a [Statement],
+        mut live: HashSet<&'a str>,
+        report: bool,
+    ) -> HashSet<&'a str> {
+        for statement in statements.iter().rev() {
+            live = self.live_before_statement(statement, live, report);
+        }
+        live
+    }
+
+    fn live_before_statement(
+        &mut self,
+        statement: &'a Statement,
+        mut live: HashSet<&'a str>,
+        report: bool,
+    ) -> HashSet<&'a str> {
+        let mut reads = Vec::new();
+        match statement {
+            Statement::Let(statement) => {
+                let name = statement.name.name.as_str();
+                match &statement.index {
+                    Some(index) => {
+                        reads.push(&statement.name);
+                        expression_reads(index, &mut reads);
+                    }
+                    None => {
+                        if report && self.tracked.contains(name) && !live.contains(name) {
+                            let message = format!("value assigned to `{}` is never read", name);
+                            let diagnostic = self.checker.diagnostic(
+                                Lint::UnreadAssignment,
+                                message,
+                                &statement.name,
+                            );
+                            self.checker.diagnostics.push(diagnostic);
+                        }
+                        live.remove(name);
+                    }
+                }
+                expression_reads(&statement.value, &mut reads);
+            }
+            Statement::If(statement) => {
+                let after = live.clone();
+                live = self.live_before(&statement.statements, live, report);
+                if let Some(else_statements) = &statement.else_statements {
+                    live.extend(self.live_before(else_statements, after, report));
+                } else {
+                    live.extend(after);
+                }
+                expression_reads(&statement.condition, &mut reads);
+            }
+            Statement::While(statement) => {
+                // the condition runs before the body and after each iteration
+                let mut condition_reads = Vec::new();
+                expression_reads(&statement.condition, &mut condition_reads);
+                live.extend(condition_reads.iter().map(|name| name.name.as_str()));
+                loop {
+                    let body = self.live_before(&statement.statements, live.clone(), false);
+                    if body.is_subset(&live) {
+                        break;
+                    }
+                    live.extend(body);
+                }
+                self.live_before(&statement.statements, live.clone(), report);
+            }
+            Statement::Do(statement) => call_reads(&statement.call, &mut reads),
+            Statement::Return(statement) => {
+                live.clear();
+                if let Some(value) = &statement.value {
+                    expression_reads(value, &mut reads);
+                }
+            }
+        }
+        live.extend(reads.iter().map(|name| name.name.as_str()));
+        live
+    }
+}
+
+fn statements_reads<'a>(statements: &'a [Statement], reads: &mut Vec<&'a Identifier>) {
+    for statement in statements {
+        match statement {
+            Statement::Let(statement) => {
+                if let Some(index) = &statement.index {
+                    reads.push(&statement.name);
+                    expression_reads(index, reads);
+                }
+                expression_reads(&statement.value, reads);
+            }
+            Statement::If(statement) => {
+                expression_reads(&statement.condition, reads);
+                statements_reads(&statement.statements, reads);
+                if let Some(else_statements) = &statement.else_statements {
+                    statements_reads(else_statements, reads);
+                }
+            }
+            Statement::While(statement) => {
+                expression_reads(&statement.condition, reads);
+                statements_reads(&statement.statements, reads);
+            }
+            Statement::Do(statement) => call_reads(&statement.call, reads),
+            Statement::Return(statement) => {
+                if let Some(value) = &statement.value {
+                    expression_reads(value, reads);
+                }
+            }
+        }
+    }
+}
+
+fn expression_reads<'a>(expression: &'a Expression, reads: &mut Vec<&'a Identifier>) {
+    term_reads(&expression.term, reads);
+    for (_, term) in &expression.ops {
+        term_reads(term, reads);
+    }
+}
+
+fn term_reads<'a>(term: &'a Term, reads: &mut Vec<&'a Identifier>) {
+    match &term.kind {
+        TermKind::IntConst(_) | TermKind::StringConst(_) | TermKind::KeywordConstant(_) => {}
+        TermKind::VarName(name) => reads.push(name),
+        TermKind::ArrayAccess(name, index) => {
+            reads.push(name);
+            expression_reads(index, reads);
+        }
+        TermKind::SubroutineCall(call) => call_reads(call, reads),
+        TermKind::Parenthesized(expression) => expression_reads(expression, reads),
+        TermKind::Unary(_, term) => term_reads(term, reads),
+    }
+}
+
+/// The receiver of a call is read when it is a variable.
+fn call_reads<'a>(call: &'a SubroutineCall, reads: &mut Vec<&'a Identifier>) {
+    if let Some(receiver) = &call.receiver {
+        reads.push(receiver);
+    }
+    for argument in &call.arguments {
+        expression_reads(argument, reads);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::analysis::lint::{check, Lint};
+    use crate::analysis::parsed_class::ParsedClass;
+    use crate::tokenizer::jack_tokenizer::JackTokenizer;
+
+    const GAME: &str = "\
+class Game {
+    field int size, speed;
+    static int count;
+    method void run(int x, int y) {
+        var int size, step, unused;
+        let step = 1;
+        let step = x;
+        let size = 0;
+        while (size < 10) {
+            let size = size + step;
+        }
+        return;
+    }
+}
+";
+
+    fn messages(src: &str, lints: &[Lint]) -> Vec<String> {
+        let tokenizer =
+            JackTokenizer::from_source(src, Some(Path::new("Game.jack")), Default::default())
+                .unwrap();
+        let parsed = ParsedClass::parse(tokenizer).unwrap();
+        check(&[parsed], lints)
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn reports_every_lint_in_source_order() {
+        assert_eq!(
+            vec![
+                "Game.jack:2:15: warning[unused-field]: unused field `size`",
+                "Game.jack:2:21: warning[unused-field]: unused field `speed`",
+                "Game.jack:3:16: warning[unused-static]: unused static variable `count`",
+                "Game.jack:4:32: warning[unused-parameter]: unused parameter `y`",
+                "Game.jack:5:17: warning[shadowing]: variable `size` shadows field `size`",
+                "Game.jack:5:29: warning[unused-variable]: unused variable `unused`",
+                "Game.jack:6:13: warning[unread-assignment]: value assigned to `step` is never read",
+            ],
+            messages(GAME, &Lint::ALL)
+        );
+    }
+
+    #[test]
+    fn only_enabled_lints_are_reported() {
+        assert_eq!(
+            vec!["Game.jack:5:17: warning[shadowing]: variable `size` shadows field `size`"],
+            messages(GAME, &[Lint::Shadowing])
+        );
+        assert_eq!(Vec::<String>::new(), messages(GAME, &[]));
+    }
+
+    #[test]
+    fn assignments_read_by_a_later_iteration_are_live() {
+        let src = "\
+class Game {
+    function int count(int n) {
+        var int i, last;
+        let i = 0;
+        while (i < n) {
+            let last = i;
+            let i = i + 1;
+        }
+        if (n > 0) { let last = 1; } else { let i = 0; }
+        return last;
+    }
+}
+";
+
+        assert_eq!(
+            vec!["Game.jack:9:49: warning[unread-assignment]: value assigned to `i` is never read"],
+            messages(src, &Lint::ALL)
+        );
+    }
+
+    #[test]
+    fn can_find_lints_by_name() {
+        for lint in Lint::ALL {
+            assert_eq!(Some(lint), Lint::from_name(lint.name()));
+        }
+        assert_eq!(None, Lint::from_name("unused"));
+    }
+}
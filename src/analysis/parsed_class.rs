@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::ast::Class;
+use crate::compilation::parser::Parser;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::tokenizer::jack_tokenizer::JackTokenizer;
+use crate::tokenizer::span::{LineIndex, Span};
+
+/// The `ast` of a jack file together with its source, for reporting diagnostics.
+#[derive(Debug, Clone)]
+pub struct ParsedClass {
+    pub path: PathBuf,
+    pub class: Class,
+    source: String,
+    line_index: LineIndex,
+}
+
+impl ParsedClass {
+    pub fn parse(tokenizer: JackTokenizer) -> Result<Self> {
+        let path = tokenizer.path().to_path_buf();
+        let source = tokenizer.source().to_string();
+        let class = Parser::new(tokenizer).parse_class()?;
+        Ok(ParsedClass {
+            path,
+            class,
+            line_index: LineIndex::new(&source),
+            source,
+        })
+    }
+
+    /// Returns the text of the 1-based `line` without its line break.
+    pub fn line_text(&self, line: usize) -> &str {
+        self.line_index.line_text(&self.source, line)
+    }
+
+    pub fn diagnostic(&self, severity: Severity, message: String, span: Span) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code: None,
+            message,
+            path: self.path.clone(),
+            span,
+            line_text: self.line_text(span.line).to_string(),
+            note: None,
+        }
+    }
+}
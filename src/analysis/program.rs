@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use crate::analysis::jack_os::{os_class, OS_CLASSES};
+use crate::analysis::parsed_class::ParsedClass;
+use crate::ast::{Class, SubroutineDec, SubroutineKind};
+
+/// The declared interface of a subroutine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubroutineSignature {
+    pub kind: SubroutineKind,
+    /// `None` for `void`.
+    pub return_type: Option<String>,
+    pub parameter_types: Vec<String>,
+}
+
+impl SubroutineSignature {
+    pub fn from(subroutine_dec: &SubroutineDec) -> Self {
+        SubroutineSignature {
+            kind: subroutine_dec.kind,
+            return_type: subroutine_dec
+                .return_type
+                .as_ref()
+                .map(|return_type| return_type.name().to_string()),
+            parameter_types: subroutine_dec
+                .parameters
+                .iter()
+                .map(|parameter| parameter.var_type.name().to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSignature {
+    pub subroutines: HashMap<String, SubroutineSignature>,
+    /// Whether this is a Jack OS class.
+    pub builtin: bool,
+    /// Whether all subroutines are known, which they are not for a class with syntax errors.
+    pub complete: bool,
+}
+
+impl ClassSignature {
+    fn from(class: &Class, complete: bool) -> Self {
+        let mut subroutines = HashMap::new();
+        for subroutine_dec in &class.subroutine_decs {
+            subroutines
+                .entry(subroutine_dec.name.name.clone())
+                .or_insert_with(|| SubroutineSignature::from(subroutine_dec));
+        }
+        ClassSignature {
+            subroutines,
+            builtin: false,
+            complete,
+        }
+    }
+}
+
+/// The classes visible to every class of a program, with their subroutines.
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    classes: HashMap<String, ClassSignature>,
+}
+
+impl Program {
+    /// Indexes the OS classes and `classes`. A class of `classes` replaces the OS class of its name,
+    /// but a later declaration of a name in `classes` does not replace the first.
+    pub fn new(classes: &[ParsedClass]) -> Self {
+        let mut program = Program::default();
+        for name in OS_CLASSES {
+            if let Some(signature) = os_class(name) {
+                program.classes.insert(name.to_string(), signature);
+            }
+        }
+        for parsed in classes {
+            program.declare(
+                &parsed.class.name.name,
+                ClassSignature::from(&parsed.class, true),
+            );
+        }
+        program
+    }
+
+    /// Adds the class `name` of a file with syntax errors, with the subroutines of what could be
+    /// recovered of it, so that its uses are not reported as undeclared.
+    pub fn add_broken(&mut self, name: &str, class: Option<&Class>) {
+        let signature = match class {
+            Some(class) => ClassSignature::from(class, false),
+            None => ClassSignature {
+                subroutines: HashMap::new(),
+                builtin: false,
+                complete: false,
+            },
+        };
+        self.declare(name, signature);
+    }
+
+    fn declare(&mut self, name: &str, signature: ClassSignature) {
+        if self.classes.get(name).is_none_or(|class| class.builtin) {
+            self.classes.insert(name.to_string(), signature);
+        }
+    }
+
+    pub fn class(&self, name: &str) -> Option<&ClassSignature> {
+        self.classes.get(name)
+    }
+
+    pub fn has_class(&self, name: &str) -> bool {
+        self.classes.contains_key(name)
+    }
+}
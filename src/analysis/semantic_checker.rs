@@ -0,0 +1,570 @@
+use std::collections::HashMap;
+
+use crate::analysis::parsed_class::ParsedClass;
+use crate::analysis::program::Program;
+use crate::ast::{
+    ClassVarDec, ClassVarKind, Expression, Identifier, KeywordConstant, Statement, SubroutineCall,
+    SubroutineDec, SubroutineKind, Term, TermKind, Type,
+};
+use crate::compilation::symbol_table::{SymbolKind, SymbolTable};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::tokenizer::span::Span;
+
+/// Reports undeclared identifiers, duplicate declarations, calls to subroutines that do not exist
+/// and misuses of subroutine kinds in `classes`, which form one program together with the Jack OS.
+/// A class named like an OS class replaces it, as when implementing the OS itself. The `program`
+/// declares the classes that are used.
+pub fn check(program: &Program, classes: &[ParsedClass]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut declared: HashMap<&str, &ParsedClass> = HashMap::new();
+    for parsed in classes {
+        let name = &parsed.class.name;
+        if let Some(first) = declared.get(name.name.as_str()) {
+            let message = format!("duplicate declaration of class `{}`", name.name);
+            let mut diagnostic = parsed.diagnostic(Severity::Error, message, name.span);
+            diagnostic.note = Some(format!(
+                "`{}` is first declared in {}",
+                name.name,
+                first.path.display()
+            ));
+            diagnostics.push(diagnostic);
+        } else {
+            declared.insert(&name.name, parsed);
+        }
+        diagnostics.extend(SemanticChecker::new(parsed, program).check());
+    }
+    diagnostics
+}
+
+/// Checks the names used in one class against its own declarations and the `Program`.
+struct SemanticChecker<'a> {
+    parsed: &'a ParsedClass,
+    program: &'a Program,
+    symbol_table: SymbolTable,
+    subroutine: Option<&'a SubroutineDec>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> SemanticChecker<'a> {
+    fn new(parsed: &'a ParsedClass, program: &'a Program) -> Self {
+        SemanticChecker {
+            parsed,
+            program,
+            symbol_table: SymbolTable::new(),
+            subroutine: None,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn check(mut self) -> Vec<Diagnostic> {
+        let class = &self.parsed.class;
+        for class_var_dec in &class.class_var_decs {
+            self.check_class_var_dec(class_var_dec);
+        }
+        let mut subroutines: HashMap<&str, &Identifier> = HashMap::new();
+        for subroutine_dec in &class.subroutine_decs {
+            let name = &subroutine_dec.name;
+            match subroutines.get(name.name.as_str()) {
+                Some(first) => self.duplicate("subroutine", name, first),
+                None => {
+                    subroutines.insert(&name.name, name);
+                }
+            }
+            self.check_subroutine_dec(subroutine_dec);
+        }
+        self.diagnostics
+    }
+
+    fn check_class_var_dec(&mut self, class_var_dec: &ClassVarDec) {
+        self.check_type(&class_var_dec.var_type);
+        let kind = match class_var_dec.kind {
+            ClassVarKind::Static => SymbolKind::Static,
+            ClassVarKind::Field => SymbolKind::Field,
+        };
+        for name in &class_var_dec.names {
+            self.define(name, &class_var_dec.var_type, kind);
+        }
+    }
+
+    fn check_subroutine_dec(&mut self, subroutine_dec: &'a SubroutineDec) {
+        let class_name = &self.parsed.class.name.name;
+        self.subroutine = Some(subroutine_dec);
+        let this_type = (subroutine_dec.kind == SubroutineKind::Method).then_some(class_name);
+        self.symbol_table
+            .start_subroutine(this_type.map(String::as_str));
+        if let Some(return_type) = &subroutine_dec.return_type {
+            self.check_type(return_type);
+        }
+        if subroutine_dec.kind == SubroutineKind::Constructor
+            && subroutine_dec.return_type.as_ref().map(Type::name) != Some(class_name)
+        {
+            let message = format!(
+                "constructor `{}` must return its class `{}`",
+                subroutine_dec.name.name, class_name
+            );
+            self.error(message, &subroutine_dec.name);
+        }
+        for parameter in &subroutine_dec.parameters {
+            self.check_type(&parameter.var_type);
+            self.define(&parameter.name, &parameter.var_type, SymbolKind::Argument);
+        }
+        for var_dec in &subroutine_dec.body.var_decs {
+            self.check_type(&var_dec.var_type);
+            for name in &var_dec.names {
+                self.define(name, &var_dec.var_type, SymbolKind::Local);
+            }
+        }
+        self.check_statements(&subroutine_dec.body.statements);
+    }
+
+    fn check_statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.check_statement(statement);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let(statement) => {
+                self.check_variable(&statement.name);
+                if let Some(index) = &statement.index {
+                    self.check_expression(index);
+                }
+                self.check_expression(&statement.value);
+            }
+            Statement::If(statement) => {
+                self.check_expression(&statement.condition);
+                self.check_statements(&statement.statements);
+                if let Some(else_statements) = &statement.else_statements {
+                    self.check_statements(else_statements);
+                }
+            }
+            Statement::While(statement) => {
+                self.check_expression(&statement.condition);
+                self.check_statements(&statement.statements);
+            }
+            Statement::Do(statement) => self.check_subroutine_call(&statement.call),
+            Statement::Return(statement) => {
+                if let Some(value) = &statement.value {
+                    self.check_expression(value);
+                }
+                let subroutine = self.subroutine.unwrap();
+                if subroutine.kind == SubroutineKind::Constructor
+                    && !statement.value.as_ref().is_some_and(is_this)
+                {
+                    let message =
+                        format!("constructor `{}` must return `this`", subroutine.name.name);
+                    self.error_at(message, statement.span);
+                }
+            }
+        }
+    }
+
+    fn check_expression(&mut self, expression: &Expression) {
+        self.check_term(&expression.term);
+        for (_, term) in &expression.ops {
+            self.check_term(term);
+        }
+    }
+
+    fn check_term(&mut self, term: &Term) {
+        match &term.kind {
+            TermKind::IntConst(_) | TermKind::StringConst(_) | TermKind::KeywordConstant(_) => {}
+            TermKind::VarName(name) => self.check_variable(name),
+            TermKind::ArrayAccess(name, index) => {
+                self.check_variable(name);
+                self.check_expression(index);
+            }
+            TermKind::SubroutineCall(call) => self.check_subroutine_call(call),
+            TermKind::Parenthesized(expression) => self.check_expression(expression),
+            TermKind::Unary(_, term) => self.check_term(term),
+        }
+    }
+
+    fn check_subroutine_call(&mut self, call: &SubroutineCall) {
+        // whether the call passes an object, which is `this` when there is no receiver
+        let mut on_object = true;
+        let class_name = match &call.receiver {
+            None => Some(self.parsed.class.name.name.clone()),
+            Some(receiver) => match self.symbol_table.get(&receiver.name) {
+                // an undeclared type was reported at the declaration of the variable
+                Some(symbol) => Some(symbol.var_type.clone()),
+                None if self.program.has_class(&receiver.name) => {
+                    on_object = false;
+                    Some(receiver.name.clone())
+                }
+                None => {
+                    let message = format!("undeclared class or variable `{}`", receiver.name);
+                    self.error(message, receiver);
+                    None
+                }
+            },
+        };
+        let class = class_name
+            .as_ref()
+            .and_then(|class_name| self.program.class(class_name));
+        if let (Some(class_name), Some(class)) = (class_name.as_ref(), class) {
+            match class.subroutines.get(&call.name.name) {
+                Some(signature) => {
+                    self.check_call_kind(call, class_name, signature.kind, on_object)
+                }
+                // the subroutine may be in a broken part of the class
+                None if !class.complete => {}
+                None => {
+                    let message = format!(
+                        "subroutine `{}` is not declared in class `{}`",
+                        call.name.name, class_name
+                    );
+                    self.error(message, &call.name);
+                }
+            }
+        }
+        for argument in &call.arguments {
+            self.check_expression(argument);
+        }
+    }
+
+    /// Checks that methods are called on an object and functions and constructors on their class.
+    fn check_call_kind(
+        &mut self,
+        call: &SubroutineCall,
+        class_name: &str,
+        kind: SubroutineKind,
+        on_object: bool,
+    ) {
+        let subroutine = self.subroutine.unwrap();
+        let message = match (kind, on_object) {
+            (SubroutineKind::Method, false) => format!(
+                "method `{}` of class `{}` must be called on an object",
+                call.name.name, class_name
+            ),
+            (SubroutineKind::Method, true)
+                if call.receiver.is_none() && subroutine.kind == SubroutineKind::Function =>
+            {
+                format!(
+                    "function `{}` cannot call method `{}` without an object",
+                    subroutine.name.name, call.name.name
+                )
+            }
+            (SubroutineKind::Method, _) | (_, false) => return,
+            (_, true) => format!(
+                "{} `{}` of class `{}` must be called as `{}.{}`",
+                kind.keyword(),
+                call.name.name,
+                class_name,
+                class_name,
+                call.name.name
+            ),
+        };
+        self.error(message, &call.name);
+    }
+
+    fn check_variable(&mut self, name: &Identifier) {
+        match self.symbol_table.kind_of(&name.name) {
+            None => self.error(format!("undeclared variable `{}`", name.name), name),
+            Some(SymbolKind::Field) => {
+                let subroutine = self.subroutine.unwrap();
+                if subroutine.kind == SubroutineKind::Function {
+                    let message = format!(
+                        "function `{}` cannot use field `{}`",
+                        subroutine.name.name, name.name
+                    );
+                    self.error(message, name);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    fn check_type(&mut self, var_type: &Type) {
+        if let Type::Class(class_name) = var_type {
+            if !self.program.has_class(&class_name.name) {
+                self.error(
+                    format!("undeclared class `{}`", class_name.name),
+                    class_name,
+                );
+            }
+        }
+    }
+
+    /// Defines a variable, reporting it if its scope already has one with the same name.
+    fn define(&mut self, name: &Identifier, var_type: &Type, kind: SymbolKind) {
+        if let Some(first) = self.symbol_table.get_in_scope_of(kind, &name.name) {
+            let first = Identifier {
+                name: name.name.clone(),
+                span: first.span,
+            };
+            self.duplicate("variable", name, &first);
+            return;
+        }
+        self.symbol_table
+            .define(&name.name, var_type.name(), kind, name.span);
+    }
+
+    fn duplicate(&mut self, what: &str, name: &Identifier, first: &Identifier) {
+        let message = format!("duplicate declaration of {} `{}`", what, name.name);
+        let mut diagnostic = self.parsed.diagnostic(Severity::Error, message, name.span);
+        diagnostic.note = Some(format!(
+            "`{}` is first declared at line {}",
+            name.name, first.span.line
+        ));
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn error(&mut self, message: String, identifier: &Identifier) {
+        self.error_at(message, identifier.span);
+    }
+
+    fn error_at(&mut self, message: String, span: Span) {
+        let diagnostic = self.parsed.diagnostic(Severity::Error, message, span);
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+fn is_this(expression: &Expression) -> bool {
+    expression.ops.is_empty()
+        && expression.term.kind == TermKind::KeywordConstant(KeywordConstant::This)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::analysis::parsed_class::ParsedClass;
+    use crate::analysis::program::Program;
+    use crate::analysis::semantic_checker;
+    use crate::diagnostic::Diagnostic;
+    use crate::tokenizer::jack_tokenizer::JackTokenizer;
+
+    fn parse(name: &str, src: &str) -> ParsedClass {
+        let tokenizer =
+            JackTokenizer::from_source(src, Some(Path::new(name)), Default::default()).unwrap();
+        ParsedClass::parse(tokenizer).unwrap()
+    }
+
+    fn check(classes: &[ParsedClass]) -> Vec<Diagnostic> {
+        semantic_checker::check(&Program::new(classes), classes)
+    }
+
+    fn messages(classes: &[ParsedClass]) -> Vec<String> {
+        check(classes)
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn valid_program_has_no_errors() {
+        let main = parse(
+            "Main.jack",
+            "\
+class Main {
+    function void main() {
+        var Point p;
+        let p = Point.new(1, 2);
+        do p.draw();
+        do Output.printInt(p.getX());
+        do Screen.drawRectangle(0, 0, 10, 10);
+        do Memory.deAlloc(p);
+        return;
+    }
+}
+",
+        );
+        let point = parse(
+            "Point.jack",
+            "\
+class Point {
+    field int x, y;
+    constructor Point new(int ax, int ay) {
+        let x = ax;
+        let y = ay;
+        return this;
+    }
+    method int getX() { return x; }
+    method void draw() {
+        do Screen.drawPixel(x, y);
+        do erase();
+        return;
+    }
+    method void erase() { return; }
+}
+",
+        );
+
+        assert_eq!(Vec::<String>::new(), messages(&[main, point]));
+    }
+
+    #[test]
+    fn reports_undeclared_names() {
+        let main = parse(
+            "Main.jack",
+            "\
+class Main {
+    function void main() {
+        var Foo f;
+        let x = 1;
+        do Bar.run(y);
+        do Main.missing();
+        do helper();
+        return;
+    }
+}
+",
+        );
+
+        assert_eq!(
+            vec![
+                "Main.jack:3:13: error: undeclared class `Foo`",
+                "Main.jack:4:13: error: undeclared variable `x`",
+                "Main.jack:5:12: error: undeclared class or variable `Bar`",
+                "Main.jack:5:20: error: undeclared variable `y`",
+                "Main.jack:6:17: error: subroutine `missing` is not declared in class `Main`",
+                "Main.jack:7:12: error: subroutine `helper` is not declared in class `Main`",
+            ],
+            messages(&[main])
+        );
+    }
+
+    #[test]
+    fn reports_duplicate_declarations() {
+        let main = parse(
+            "Main.jack",
+            "\
+class Main {
+    field int x;
+    static boolean x;
+    function void main(int a) {
+        var int a, b, b;
+        var int x;
+        return;
+    }
+    function void main() {
+        return;
+    }
+}
+",
+        );
+        let other = parse("Other.jack", "class Main {}\n");
+
+        let diagnostics = check(&[main, other]);
+        let messages: Vec<String> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "Main.jack:3:20: error: duplicate declaration of variable `x`",
+                "Main.jack:5:17: error: duplicate declaration of variable `a`",
+                "Main.jack:5:23: error: duplicate declaration of variable `b`",
+                "Main.jack:9:19: error: duplicate declaration of subroutine `main`",
+                "Other.jack:1:7: error: duplicate declaration of class `Main`",
+            ],
+            messages
+        );
+        assert_eq!(
+            Some("`x` is first declared at line 2".to_string()),
+            diagnostics[0].note
+        );
+    }
+
+    #[test]
+    fn reports_calls_to_missing_os_subroutines() {
+        let main = parse(
+            "Main.jack",
+            "\
+class Main {
+    function void main() {
+        var String s;
+        let s = Keyboard.readLine(\"name? \");
+        do Output.printStrin(s);
+        do s.appendChar(33);
+        do s.append(33);
+        return;
+    }
+}
+",
+        );
+
+        assert_eq!(
+            vec![
+                "Main.jack:5:19: error: subroutine `printStrin` is not declared in class `Output`",
+                "Main.jack:7:14: error: subroutine `append` is not declared in class `String`",
+            ],
+            messages(&[main])
+        );
+    }
+
+    #[test]
+    fn user_class_replaces_os_class() {
+        let math = parse(
+            "Math.jack",
+            "\
+class Math {
+    function int multiply(int x, int y) { return 0; }
+    function int cube(int x) { return Math.multiply(x, Math.multiply(x, x)); }
+}
+",
+        );
+        let main = parse(
+            "Main.jack",
+            "\
+class Main {
+    function void main() {
+        do Output.printInt(Math.cube(3));
+        do Math.sqrt(9);
+        return;
+    }
+}
+",
+        );
+
+        assert_eq!(
+            vec!["Main.jack:4:17: error: subroutine `sqrt` is not declared in class `Math`"],
+            messages(&[math, main])
+        );
+    }
+
+    #[test]
+    fn reports_misused_subroutine_kinds() {
+        let point = parse(
+            "Point.jack",
+            "\
+class Point {
+    field int x;
+    constructor Point new() {
+        let x = 0;
+        return x;
+    }
+    constructor int origin() { return this; }
+    method int getX() { return x; }
+    function int twice() {
+        do Point.getX();
+        return getX() + x;
+    }
+    function void main() {
+        var Point p;
+        let p = Point.new();
+        do p.twice();
+        do twice();
+        return;
+    }
+}
+",
+        );
+
+        assert_eq!(
+            vec![
+                "Point.jack:5:9: error: constructor `new` must return `this`",
+                "Point.jack:7:21: error: constructor `origin` must return its class `Point`",
+                "Point.jack:10:18: error: method `getX` of class `Point` must be called on an object",
+                "Point.jack:11:16: error: function `twice` cannot call method `getX` without an object",
+                "Point.jack:11:25: error: function `twice` cannot use field `x`",
+                "Point.jack:16:14: error: function `twice` of class `Point` must be called as `Point.twice`",
+                "Point.jack:17:12: error: function `twice` of class `Point` must be called as `Point.twice`",
+            ],
+            messages(&[point])
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::diagnostic::Diagnostic;
+
+/// The semantic errors found in a class, e.g. undeclared variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticErrors {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl SemanticErrors {
+    /// Renders every error, separated by blank lines.
+    pub fn report(&self) -> String {
+        let reports: Vec<String> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.report())
+            .collect();
+        reports.join("\n")
+    }
+}
+
+impl fmt::Display for SemanticErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let diagnostics: Vec<String> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        write!(f, "{}", diagnostics.join("\n"))
+    }
+}
+
+impl std::error::Error for SemanticErrors {}
@@ -0,0 +1,539 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::analysis::parsed_class::ParsedClass;
+use crate::analysis::program::{Program, SubroutineSignature};
+use crate::ast::{
+    BinaryOp, Expression, KeywordConstant, Statement, SubroutineCall, SubroutineDec,
+    SubroutineKind, Term, TermKind, UnaryOp,
+};
+use crate::compilation::symbol_table::SymbolTable;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::tokenizer::span::Span;
+
+/// How much of Jack's loose typing is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Strictness {
+    /// Only mistakes that break the generated code: wrong argument counts and `this` in functions.
+    Lenient,
+    /// Also booleans mixed with other types, objects of unrelated classes and arithmetic on objects.
+    #[default]
+    Standard,
+    /// Also `int` and `char` mixed, numbers used as objects and conditions that are not `boolean`.
+    Strict,
+}
+
+/// The type of an expression as far as it can be inferred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JackType {
+    Int,
+    Char,
+    Boolean,
+    Class(String),
+    Null,
+    /// An array element, the result of a `void` call or an undeclared name.
+    Unknown,
+}
+
+impl JackType {
+    /// The type of a declaration such as `int` or `Point`.
+    pub fn from(name: &str) -> Self {
+        match name {
+            "int" => JackType::Int,
+            "char" => JackType::Char,
+            "boolean" => JackType::Boolean,
+            _ => JackType::Class(name.to_string()),
+        }
+    }
+
+    fn is_object(&self) -> bool {
+        matches!(self, JackType::Class(_) | JackType::Null)
+    }
+
+    fn is_array(&self) -> bool {
+        *self == JackType::Class("Array".to_string())
+    }
+}
+
+impl fmt::Display for JackType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            JackType::Int => write!(f, "int"),
+            JackType::Char => write!(f, "char"),
+            JackType::Boolean => write!(f, "boolean"),
+            JackType::Class(name) => write!(f, "{}", name),
+            JackType::Null => write!(f, "null"),
+            JackType::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Reports type mistakes in `classes` at the given `strictness`. Undeclared names are left to the
+/// semantic checker.
+pub fn check(
+    program: &Program,
+    classes: &[ParsedClass],
+    strictness: Strictness,
+) -> Vec<Diagnostic> {
+    classes
+        .iter()
+        .flat_map(|parsed| TypeChecker::new(parsed, program, strictness).check())
+        .collect()
+}
+
+struct TypeChecker<'a> {
+    parsed: &'a ParsedClass,
+    program: &'a Program,
+    strictness: Strictness,
+    symbol_table: SymbolTable,
+    subroutine: Option<&'a SubroutineDec>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> TypeChecker<'a> {
+    fn new(parsed: &'a ParsedClass, program: &'a Program, strictness: Strictness) -> Self {
+        TypeChecker {
+            parsed,
+            program,
+            strictness,
+            symbol_table: SymbolTable::new(),
+            subroutine: None,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn check(mut self) -> Vec<Diagnostic> {
+        let class = &self.parsed.class;
+        for class_var_dec in &class.class_var_decs {
+            self.symbol_table.define_class_var_dec(class_var_dec);
+        }
+        for subroutine_dec in &class.subroutine_decs {
+            let this_type =
+                (subroutine_dec.kind == SubroutineKind::Method).then_some(class.name.name.as_str());
+            self.symbol_table.start_subroutine(this_type);
+            self.symbol_table
+                .define_parameters(&subroutine_dec.parameters);
+            for var_dec in &subroutine_dec.body.var_decs {
+                self.symbol_table.define_var_dec(var_dec);
+            }
+            self.subroutine = Some(subroutine_dec);
+            self.check_statements(&subroutine_dec.body.statements);
+        }
+        self.diagnostics
+    }
+
+    fn check_statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.check_statement(statement);
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let(statement) => {
+                if let Some(index) = &statement.index {
+                    self.type_of_expression(index);
+                }
+                let value = self.type_of_expression(&statement.value);
+                let target = match (
+                    &statement.index,
+                    self.symbol_table.type_of(&statement.name.name),
+                ) {
+                    (None, Some(var_type)) => JackType::from(var_type),
+                    _ => JackType::Unknown,
+                };
+                if !self.is_assignable(&target, &value) {
+                    let message = format!(
+                        "cannot assign `{}` to `{}` of type `{}`",
+                        value, statement.name.name, target
+                    );
+                    self.warning(message, statement.value.span);
+                }
+            }
+            Statement::If(statement) => {
+                self.check_condition(&statement.condition);
+                self.check_statements(&statement.statements);
+                if let Some(else_statements) = &statement.else_statements {
+                    self.check_statements(else_statements);
+                }
+            }
+            Statement::While(statement) => {
+                self.check_condition(&statement.condition);
+                self.check_statements(&statement.statements);
+            }
+            Statement::Do(statement) => {
+                self.type_of_call(&statement.call);
+            }
+            Statement::Return(statement) => {
+                let Some(value) = &statement.value else {
+                    return;
+                };
+                let value_type = self.type_of_expression(value);
+                let return_type = self
+                    .subroutine
+                    .and_then(|subroutine| subroutine.return_type.as_ref())
+                    .map_or(JackType::Unknown, |return_type| {
+                        JackType::from(return_type.name())
+                    });
+                if !self.is_assignable(&return_type, &value_type) {
+                    let message = format!(
+                        "returns `{}`, but the declared return type is `{}`",
+                        value_type, return_type
+                    );
+                    self.warning(message, value.span);
+                }
+            }
+        }
+    }
+
+    fn check_condition(&mut self, condition: &Expression) {
+        let condition_type = self.type_of_expression(condition);
+        if self.strictness == Strictness::Strict
+            && !matches!(condition_type, JackType::Boolean | JackType::Unknown)
+        {
+            let message = format!(
+                "condition has type `{}`, expected `boolean`",
+                condition_type
+            );
+            self.warning(message, condition.span);
+        }
+    }
+
+    fn type_of_expression(&mut self, expression: &Expression) -> JackType {
+        let mut left = self.type_of_term(&expression.term);
+        // the operations apply from left to right, so the left operand is everything before `op`
+        let mut left_span = expression.term.span;
+        for (op, term) in &expression.ops {
+            let right = self.type_of_term(term);
+            left = match op {
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
+                    self.check_operand(op.symbol(), &left, left_span);
+                    self.check_operand(op.symbol(), &right, term.span);
+                    JackType::Int
+                }
+                BinaryOp::Lt | BinaryOp::Gt => {
+                    self.check_operand(op.symbol(), &left, left_span);
+                    self.check_operand(op.symbol(), &right, term.span);
+                    JackType::Boolean
+                }
+                BinaryOp::Eq => JackType::Boolean,
+                BinaryOp::And | BinaryOp::Or => match (&left, &right) {
+                    (JackType::Unknown, _) | (_, JackType::Unknown) => JackType::Unknown,
+                    (JackType::Boolean, JackType::Boolean) => JackType::Boolean,
+                    (JackType::Boolean, _) | (_, JackType::Boolean)
+                        if self.strictness >= Strictness::Standard =>
+                    {
+                        let message = format!(
+                            "operator `{}` mixes `{}` and `{}`",
+                            op.symbol(),
+                            left,
+                            right
+                        );
+                        self.warning(message, left_span.to(term.span));
+                        JackType::Unknown
+                    }
+                    _ => JackType::Int,
+                },
+            };
+            left_span = left_span.to(term.span);
+        }
+        left
+    }
+
+    fn type_of_term(&mut self, term: &Term) -> JackType {
+        match &term.kind {
+            TermKind::IntConst(_) => JackType::Int,
+            TermKind::StringConst(_) => JackType::Class("String".to_string()),
+            TermKind::KeywordConstant(KeywordConstant::True | KeywordConstant::False) => {
+                JackType::Boolean
+            }
+            TermKind::KeywordConstant(KeywordConstant::Null) => JackType::Null,
+            TermKind::KeywordConstant(KeywordConstant::This) => match self.subroutine {
+                Some(subroutine) if subroutine.kind == SubroutineKind::Function => {
+                    let message = format!(
+                        "`this` cannot be used in function `{}`",
+                        subroutine.name.name
+                    );
+                    self.error(message, term.span);
+                    JackType::Unknown
+                }
+                _ => JackType::Class(self.parsed.class.name.name.clone()),
+            },
+            TermKind::VarName(name) => self
+                .symbol_table
+                .type_of(&name.name)
+                .map_or(JackType::Unknown, JackType::from),
+            TermKind::ArrayAccess(_, index) => {
+                self.type_of_expression(index);
+                JackType::Unknown
+            }
+            TermKind::SubroutineCall(call) => self.type_of_call(call),
+            TermKind::Parenthesized(expression) => self.type_of_expression(expression),
+            TermKind::Unary(op, operand) => {
+                let operand_type = self.type_of_term(operand);
+                match op {
+                    UnaryOp::Neg => {
+                        self.check_operand(op.symbol(), &operand_type, operand.span);
+                        JackType::Int
+                    }
+                    UnaryOp::Not => operand_type,
+                }
+            }
+        }
+    }
+
+    /// Checks the arguments of `call` against the signature of the called subroutine and returns
+    /// its return type.
+    fn type_of_call(&mut self, call: &SubroutineCall) -> JackType {
+        let argument_types: Vec<JackType> = call
+            .arguments
+            .iter()
+            .map(|argument| self.type_of_expression(argument))
+            .collect();
+        let Some((class_name, signature)) = self.signature_of(call) else {
+            return JackType::Unknown;
+        };
+        let full_name = format!("{}.{}", class_name, call.name.name);
+        if signature.parameter_types.len() != argument_types.len() {
+            let message = format!(
+                "`{}` takes {} argument{} but {} {} given",
+                full_name,
+                signature.parameter_types.len(),
+                if signature.parameter_types.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                argument_types.len(),
+                if argument_types.len() == 1 {
+                    "was"
+                } else {
+                    "were"
+                },
+            );
+            self.error(message, call.span);
+        } else {
+            let parameters = signature.parameter_types.iter().zip(&argument_types);
+            for (i, (parameter_type, argument_type)) in parameters.enumerate() {
+                let parameter_type = JackType::from(parameter_type);
+                if !self.is_assignable(&parameter_type, argument_type) {
+                    let message = format!(
+                        "argument {} of `{}` has type `{}`, expected `{}`",
+                        i + 1,
+                        full_name,
+                        argument_type,
+                        parameter_type
+                    );
+                    self.warning(message, call.arguments[i].span);
+                }
+            }
+        }
+        signature
+            .return_type
+            .as_deref()
+            .map_or(JackType::Unknown, JackType::from)
+    }
+
+    /// The class and signature of the subroutine `call` refers to, if it is declared.
+    fn signature_of(&self, call: &SubroutineCall) -> Option<(String, &'a SubroutineSignature)> {
+        let class_name = match &call.receiver {
+            None => self.parsed.class.name.name.clone(),
+            Some(receiver) => match self.symbol_table.type_of(&receiver.name) {
+                Some(var_type) => var_type.to_string(),
+                None => receiver.name.clone(),
+            },
+        };
+        let program: &'a Program = self.program;
+        let signature = program
+            .class(&class_name)?
+            .subroutines
+            .get(&call.name.name)?;
+        Some((class_name, signature))
+    }
+
+    /// Whether a value of type `value` may be stored where `target` is expected.
+    fn is_assignable(&self, target: &JackType, value: &JackType) -> bool {
+        match (target, value) {
+            _ if self.strictness == Strictness::Lenient => true,
+            (JackType::Unknown, _) | (_, JackType::Unknown) => true,
+            _ if target == value => true,
+            (JackType::Class(_), JackType::Null) => true,
+            _ if self.strictness == Strictness::Strict => false,
+            (JackType::Int | JackType::Char, JackType::Int | JackType::Char) => true,
+            (JackType::Class(_), JackType::Class(_)) => target.is_array() || value.is_array(),
+            (JackType::Boolean, _) | (_, JackType::Boolean) => false,
+            // numbers are used as the addresses of objects
+            _ => true,
+        }
+    }
+
+    fn check_operand(&mut self, symbol: char, operand: &JackType, span: Span) {
+        let reported = match self.strictness {
+            Strictness::Lenient => false,
+            Strictness::Standard => operand.is_object(),
+            Strictness::Strict => operand.is_object() || *operand == JackType::Boolean,
+        };
+        if reported {
+            let message = format!("operator `{}` cannot be applied to `{}`", symbol, operand);
+            self.warning(message, span);
+        }
+    }
+
+    fn warning(&mut self, message: String, span: Span) {
+        let diagnostic = self.parsed.diagnostic(Severity::Warning, message, span);
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn error(&mut self, message: String, span: Span) {
+        let diagnostic = self.parsed.diagnostic(Severity::Error, message, span);
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::analysis::parsed_class::ParsedClass;
+    use crate::analysis::program::Program;
+    use crate::analysis::type_checker::{check, Strictness};
+    use crate::tokenizer::jack_tokenizer::JackTokenizer;
+
+    const MAIN: &str = "\
+class Main {
+    field String name; field boolean shown;
+    field Point p;
+    function void main() {
+        var int x;
+        var char c;
+        var boolean done;
+        let name = true;
+        let x = Math.max(1, 2, 3);
+        let p = this;
+        let x = p + 1;
+        let c = x;
+        let p = Memory.alloc(2);
+        do Output.printString(done);
+        if (x) { let done = null; }
+        return;
+    }
+    method int count() { return shown; }
+}
+";
+
+    fn messages(src: &str, strictness: Strictness) -> Vec<String> {
+        let tokenizer =
+            JackTokenizer::from_source(src, Some(Path::new("Main.jack")), Default::default())
+                .unwrap();
+        let parsed = ParsedClass::parse(tokenizer).unwrap();
+        let classes = [parsed];
+        check(&Program::new(&classes), &classes, strictness)
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn lenient_reports_only_what_breaks_the_code() {
+        assert_eq!(
+            vec![
+                "Main.jack:9:17: error: `Math.max` takes 2 arguments but 3 were given",
+                "Main.jack:10:17: error: `this` cannot be used in function `main`",
+            ],
+            messages(MAIN, Strictness::Lenient)
+        );
+    }
+
+    #[test]
+    fn standard_reports_obvious_mismatches() {
+        assert_eq!(
+            vec![
+                "Main.jack:8:20: warning: cannot assign `boolean` to `name` of type `String`",
+                "Main.jack:9:17: error: `Math.max` takes 2 arguments but 3 were given",
+                "Main.jack:10:17: error: `this` cannot be used in function `main`",
+                "Main.jack:11:17: warning: operator `+` cannot be applied to `Point`",
+                "Main.jack:14:31: warning: argument 1 of `Output.printString` has type `boolean`, expected `String`",
+                "Main.jack:15:29: warning: cannot assign `null` to `done` of type `boolean`",
+                "Main.jack:18:33: warning: returns `boolean`, but the declared return type is `int`",
+            ],
+            messages(MAIN, Strictness::Standard)
+        );
+    }
+
+    #[test]
+    fn strict_also_reports_loose_conversions() {
+        let strict = messages(MAIN, Strictness::Strict);
+
+        assert!(strict.contains(
+            &"Main.jack:12:17: warning: cannot assign `int` to `c` of type `char`".to_string()
+        ));
+        assert!(strict.contains(
+            &"Main.jack:13:17: warning: cannot assign `Array` to `p` of type `Point`".to_string()
+        ));
+        assert!(strict.contains(
+            &"Main.jack:15:13: warning: condition has type `int`, expected `boolean`".to_string()
+        ));
+        assert_eq!(10, strict.len());
+    }
+
+    #[test]
+    fn standard_reports_booleans_mixed_with_numbers() {
+        let src = "\
+class Main {
+    function void main() {
+        var int x;
+        var boolean done;
+        let done = true & 5;
+        let done = done | (x < 1);
+        let x = x | 5;
+        return;
+    }
+}
+";
+
+        assert_eq!(
+            vec!["Main.jack:5:20: warning: operator `&` mixes `boolean` and `int`"],
+            messages(src, Strictness::Standard)
+        );
+        assert_eq!(Vec::<String>::new(), messages(src, Strictness::Lenient));
+    }
+
+    #[test]
+    fn operand_mismatches_point_at_the_operand() {
+        let src = "\
+class Main {
+    function void main() {
+        var int x;
+        let x = x < 2 + 1 * (x = 1);
+        return;
+    }
+}
+";
+        let tokenizer =
+            JackTokenizer::from_source(src, Some(Path::new("Main.jack")), Default::default())
+                .unwrap();
+        let classes = [ParsedClass::parse(tokenizer).unwrap()];
+        let diagnostics = check(&Program::new(&classes), &classes, Strictness::Strict);
+
+        let actual: Vec<(String, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let span = diagnostic.span;
+                (diagnostic.message.clone(), &src[span.start..span.end])
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "operator `+` cannot be applied to `boolean`".to_string(),
+                    "x < 2"
+                ),
+                (
+                    "operator `*` cannot be applied to `boolean`".to_string(),
+                    "(x = 1)"
+                ),
+            ],
+            actual
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use crate::tokenizer::span::Span;
+
+/// class = ’class’ className ’{’ classVarDec* subroutineDec* ’}’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Class {
+    pub name: Identifier,
+    pub class_var_decs: Vec<ClassVarDec>,
+    pub subroutine_decs: Vec<SubroutineDec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identifier {
+    pub name: String,
+    pub span: Span,
+}
+
+/// classVarDec = (’static’ | ’field’) type varName (’,’ varName)* ’;’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassVarDec {
+    pub kind: ClassVarKind,
+    pub var_type: Type,
+    pub names: Vec<Identifier>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassVarKind {
+    Static,
+    Field,
+}
+
+/// type = ’int’ | ’char’ | ’boolean’ | className
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    Int,
+    Char,
+    Boolean,
+    Class(Identifier),
+}
+
+/// subroutineDec = (’constructor’ | ’function’ | ’method’) (’void’ | type) subroutineName ’(’ parameterList ’)’ subroutineBody
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubroutineDec {
+    pub kind: SubroutineKind,
+    /// `None` for `void`.
+    pub return_type: Option<Type>,
+    pub name: Identifier,
+    pub parameters: Vec<Parameter>,
+    pub body: SubroutineBody,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubroutineKind {
+    Constructor,
+    Function,
+    Method,
+}
+
+/// One `type varName` of a parameterList.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub var_type: Type,
+    pub name: Identifier,
+}
+
+/// subroutineBody = ’{’ varDec* statements ’}’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubroutineBody {
+    pub var_decs: Vec<VarDec>,
+    pub statements: Vec<Statement>,
+}
+
+/// varDec = ’var’ type varName (’,’ varName)* ’;’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarDec {
+    pub var_type: Type,
+    pub names: Vec<Identifier>,
+}
+
+/// statement = letStatement | ifStatement | whileStatement | doStatement | returnStatement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Let(LetStatement),
+    If(IfStatement),
+    While(WhileStatement),
+    Do(DoStatement),
+    Return(ReturnStatement),
+}
+
+/// letStatement = ’let’ varName (’[’ expression ’]’)? ’=’ expression ’;’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetStatement {
+    pub name: Identifier,
+    pub index: Option<Box<Expression>>,
+    pub value: Expression,
+    pub span: Span,
+}
+
+/// ifStatement = ’if’ ’(’ expression ’)’ ’{’ statements ’}’ (’else’ ’{’ statements ’}’)?
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfStatement {
+    pub condition: Expression,
+    pub statements: Vec<Statement>,
+    pub else_statements: Option<Vec<Statement>>,
+    pub span: Span,
+}
+
+/// whileStatement = ’while’ ’(’ expression ’)’ ’{’ statements ’}’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhileStatement {
+    pub condition: Expression,
+    pub statements: Vec<Statement>,
+    pub span: Span,
+}
+
+/// doStatement = ’do’ subroutineCall ’;’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoStatement {
+    pub call: SubroutineCall,
+    pub span: Span,
+}
+
+/// returnStatement = ’return’ expression? ’;’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReturnStatement {
+    pub value: Option<Expression>,
+    pub span: Span,
+}
+
+impl Statement {
+    /// The span of the statement's leading keyword.
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let(statement) => statement.span,
+            Statement::If(statement) => statement.span,
+            Statement::While(statement) => statement.span,
+            Statement::Do(statement) => statement.span,
+            Statement::Return(statement) => statement.span,
+        }
+    }
+}
+
+/// expression = term (op term)*
+///
+/// Jack has no operator precedence, so the operations are kept in source order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression {
+    pub term: Term,
+    pub ops: Vec<(BinaryOp, Term)>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub kind: TermKind,
+    pub span: Span,
+}
+
+/// term = integerConstant | stringConstant | keywordConstant | varName | varName ’[’ expression ’]’ | subroutineCall | ’(’ expression ’)’ | unaryOp term
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermKind {
+    IntConst(usize),
+    StringConst(String),
+    KeywordConstant(KeywordConstant),
+    VarName(Identifier),
+    ArrayAccess(Identifier, Box<Expression>),
+    SubroutineCall(SubroutineCall),
+    Parenthesized(Box<Expression>),
+    Unary(UnaryOp, Box<Term>),
+}
+
+/// subroutineCall = subroutineName ’(’ expressionList ’)’ | (className | varName) ’.’ subroutineName ’(’ expressionList ’)’
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubroutineCall {
+    /// The class or variable before the `.`, if any.
+    pub receiver: Option<Identifier>,
+    pub name: Identifier,
+    pub arguments: Vec<Expression>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    And,
+    Or,
+    Lt,
+    Gt,
+    Eq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordConstant {
+    True,
+    False,
+    Null,
+    This,
+}
+
+impl BinaryOp {
+    pub fn from(symbol: char) -> Option<BinaryOp> {
+        match symbol {
+            '+' => Some(BinaryOp::Add),
+            '-' => Some(BinaryOp::Sub),
+            '*' => Some(BinaryOp::Mul),
+            '/' => Some(BinaryOp::Div),
+            '&' => Some(BinaryOp::And),
+            '|' => Some(BinaryOp::Or),
+            '<' => Some(BinaryOp::Lt),
+            '>' => Some(BinaryOp::Gt),
+            '=' => Some(BinaryOp::Eq),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            BinaryOp::Add => '+',
+            BinaryOp::Sub => '-',
+            BinaryOp::Mul => '*',
+            BinaryOp::Div => '/',
+            BinaryOp::And => '&',
+            BinaryOp::Or => '|',
+            BinaryOp::Lt => '<',
+            BinaryOp::Gt => '>',
+            BinaryOp::Eq => '=',
+        }
+    }
+}
+
+impl UnaryOp {
+    pub fn from(symbol: char) -> Option<UnaryOp> {
+        match symbol {
+            '-' => Some(UnaryOp::Neg),
+            '~' => Some(UnaryOp::Not),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            UnaryOp::Neg => '-',
+            UnaryOp::Not => '~',
+        }
+    }
+}
+
+impl ClassVarKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            ClassVarKind::Static => "static",
+            ClassVarKind::Field => "field",
+        }
+    }
+}
+
+impl Type {
+    /// The name of the type as written in source, e.g. `int` or `Array`.
+    pub fn name(&self) -> &str {
+        match self {
+            Type::Int => "int",
+            Type::Char => "char",
+            Type::Boolean => "boolean",
+            Type::Class(class_name) => &class_name.name,
+        }
+    }
+}
+
+impl SubroutineKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            SubroutineKind::Constructor => "constructor",
+            SubroutineKind::Function => "function",
+            SubroutineKind::Method => "method",
+        }
+    }
+}
+
+impl KeywordConstant {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            KeywordConstant::True => "true",
+            KeywordConstant::False => "false",
+            KeywordConstant::Null => "null",
+            KeywordConstant::This => "this",
+        }
+    }
+}
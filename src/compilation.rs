@@ -1 +1,6 @@
 pub mod compilation_engine;
+pub mod parse_error;
+pub mod parser;
+pub mod segment;
+pub mod symbol_table;
+pub mod vm_compilation_engine;
@@ -1,14 +1,15 @@
 use std::io::Write;
 
-use anyhow::{bail, Error, Result};
+use anyhow::Result;
 
-use crate::tokenizer::jack_tokenizer::JackTokenizer;
-use crate::tokenizer::key_word::KeyWord;
-use crate::tokenizer::key_word::KeyWord::{
-    Boolean, Char, Class, Constructor, Do, Else, False, Field, Function, If, Int, Let, Method,
-    Null, Return, Static, This, True, Var, Void, While,
+use crate::ast::{
+    Class, ClassVarDec, DoStatement, Expression, Identifier, IfStatement, LetStatement, Parameter,
+    ReturnStatement, Statement, SubroutineBody, SubroutineCall, SubroutineDec, SubroutineKind,
+    Term, TermKind, Type, VarDec, WhileStatement,
 };
-use crate::tokenizer::token_type::TokenType::{Identifier, IntConst, Keyword, StringConst, Symbol};
+use crate::compilation::parser::Parser;
+use crate::compilation::symbol_table::{SymbolKind, SymbolTable};
+use crate::tokenizer::jack_tokenizer::JackTokenizer;
 
 pub trait CompilationEngine {
     fn new(tokenizer: JackTokenizer) -> Self;
@@ -32,9 +33,49 @@ pub trait CompilationEngine {
     fn compile_expression_list(&mut self, writer: &mut impl Write) -> Result<()>;
 }
 
+/// Parses with `Parser` and writes the parse tree as nand2tetris project 10 xml.
 pub struct XmlCompilationEngine {
-    tokenizer: JackTokenizer,
+    parser: Parser,
     indent: String,
+    /// Adds category, usage and index attributes to `<identifier>` elements.
+    annotated: bool,
+    class_name: String,
+    symbol_table: SymbolTable,
+}
+
+/// The category of an identifier in annotated xml.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Var,
+    Argument,
+    Static,
+    Field,
+    Class,
+    Subroutine,
+    Undefined,
+}
+
+impl Category {
+    fn name(&self) -> &'static str {
+        match self {
+            Category::Var => "var",
+            Category::Argument => "argument",
+            Category::Static => "static",
+            Category::Field => "field",
+            Category::Class => "class",
+            Category::Subroutine => "subroutine",
+            Category::Undefined => "undefined",
+        }
+    }
+
+    fn from(kind: SymbolKind) -> Category {
+        match kind {
+            SymbolKind::Static => Category::Static,
+            SymbolKind::Field => Category::Field,
+            SymbolKind::Argument => Category::Argument,
+            SymbolKind::Local => Category::Var,
+        }
+    }
 }
 
 const INDENT_COUNT: usize = 2;
@@ -42,546 +83,526 @@ const INDENT_COUNT: usize = 2;
 impl CompilationEngine for XmlCompilationEngine {
     fn new(tokenizer: JackTokenizer) -> Self {
         XmlCompilationEngine {
-            tokenizer,
+            parser: Parser::new(tokenizer),
             indent: String::new(),
+            annotated: false,
+            class_name: String::new(),
+            symbol_table: SymbolTable::new(),
         }
     }
 
-    /// class = ’class’ className ’{’ classVarDec* subroutineDec* ’}’
     fn compile_class(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <class>
+        let class = self.parser.parse_class()?;
+        self.write_class(&class, writer)
+    }
+
+    fn compile_class_var_dec(&mut self, writer: &mut impl Write) -> Result<()> {
+        let class_var_dec = self.parser.parse_class_var_dec()?;
+        self.write_class_var_dec(&class_var_dec, writer)
+    }
+
+    fn compile_type(&mut self, writer: &mut impl Write) -> Result<()> {
+        let var_type = self.parser.parse_type()?;
+        self.write_type(&var_type, writer)
+    }
+
+    fn compile_subroutine_dec(&mut self, writer: &mut impl Write) -> Result<()> {
+        let subroutine_dec = self.parser.parse_subroutine_dec()?;
+        self.write_subroutine_dec(&subroutine_dec, writer)
+    }
+
+    fn compile_parameter_list(&mut self, writer: &mut impl Write) -> Result<()> {
+        let parameters = self.parser.parse_parameter_list()?;
+        self.write_parameter_list(&parameters, writer)
+    }
+
+    fn compile_subroutine_body(&mut self, writer: &mut impl Write) -> Result<()> {
+        let body = self.parser.parse_subroutine_body()?;
+        self.write_subroutine_body(&body, writer)
+    }
+
+    fn compile_var_dec(&mut self, writer: &mut impl Write) -> Result<()> {
+        let var_dec = self.parser.parse_var_dec()?;
+        self.write_var_dec(&var_dec, writer)
+    }
+
+    fn compile_statements(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statements = self.parser.parse_statements()?;
+        self.write_statements(&statements, writer)
+    }
+
+    fn compile_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_statement()?;
+        self.write_statement(&statement, writer)
+    }
+
+    fn compile_let_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_let_statement()?;
+        self.write_let_statement(&statement, writer)
+    }
+
+    fn compile_if_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_if_statement()?;
+        self.write_if_statement(&statement, writer)
+    }
+
+    fn compile_while_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_while_statement()?;
+        self.write_while_statement(&statement, writer)
+    }
+
+    fn compile_do_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_do_statement()?;
+        self.write_do_statement(&statement, writer)
+    }
+
+    fn compile_return_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_return_statement()?;
+        self.write_return_statement(&statement, writer)
+    }
+
+    fn compile_expression(&mut self, writer: &mut impl Write) -> Result<()> {
+        let expression = self.parser.parse_expression()?;
+        self.write_expression(&expression, writer)
+    }
+
+    fn compile_term(&mut self, writer: &mut impl Write) -> Result<()> {
+        let term = self.parser.parse_term()?;
+        self.write_term(&term, writer)
+    }
+
+    fn compile_subroutine_call(&mut self, writer: &mut impl Write) -> Result<()> {
+        let call = self.parser.parse_subroutine_call()?;
+        self.write_subroutine_call(&call, writer)
+    }
+
+    fn compile_expression_list(&mut self, writer: &mut impl Write) -> Result<()> {
+        let expressions = self.parser.parse_expression_list()?;
+        self.write_expression_list(&expressions, writer)
+    }
+}
+
+impl XmlCompilationEngine {
+    /// Writes the project 11 stage-1 xml, in which identifiers carry their category,
+    /// whether they are defined or used, and the running index of variables.
+    pub fn annotated(tokenizer: JackTokenizer) -> Self {
+        XmlCompilationEngine {
+            annotated: true,
+            ..XmlCompilationEngine::new(tokenizer)
+        }
+    }
+
+    /// class = ’class’ className ’{’ classVarDec* subroutineDec* ’}’
+    fn write_class(&mut self, class: &Class, writer: &mut impl Write) -> Result<()> {
+        self.class_name = class.name.name.clone();
         self.write_start_tag("class", writer)?;
-        // ’class’
-        self.write_key_word(vec![Class], writer)?;
-        // className
-        self.write_identifier(writer)?;
-        // {
-        self.write_symbol(writer)?;
-        // classVarDec*
-        loop {
-            if !KeyWord::exists(self.tokenizer.peek()?.value()) {
-                break;
-            }
-            match KeyWord::from(self.tokenizer.peek()?.value())? {
-                Static | Field => self.compile_class_var_dec(writer)?,
-                _ => break,
-            }
+        self.write_key_word("class", writer)?;
+        self.write_identifier(&class.name, Category::Class, true, None, writer)?;
+        self.write_symbol('{', writer)?;
+        for class_var_dec in &class.class_var_decs {
+            self.write_class_var_dec(class_var_dec, writer)?;
         }
-        // subroutineDec*
-        loop {
-            if !KeyWord::exists(self.tokenizer.peek()?.value()) {
-                break;
-            }
-            match KeyWord::from(self.tokenizer.peek()?.value())? {
-                Constructor | Function | Method => self.compile_subroutine_dec(writer)?,
-                _ => break,
-            }
+        for subroutine_dec in &class.subroutine_decs {
+            self.write_subroutine_dec(subroutine_dec, writer)?;
         }
-        // }
-        self.write_symbol(writer)?;
-        // </class>
+        self.write_symbol('}', writer)?;
         self.write_end_tag("class", writer)?;
         Ok(())
     }
 
     /// classVarDec = (’static’ | ’field’) type varName (’,’ varName)* ’;’
-    fn compile_class_var_dec(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <classVarDec>
+    fn write_class_var_dec(
+        &mut self,
+        class_var_dec: &ClassVarDec,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        self.symbol_table.define_class_var_dec(class_var_dec);
         self.write_start_tag("classVarDec", writer)?;
-        // static or field
-        self.write_key_word(vec![Static, Field], writer)?;
-        // type
-        self.compile_type(writer)?;
-        // varName
-        self.write_identifier(writer)?;
-        // (’,’ varName)*
-        while self.tokenizer.peek()?.value() == "," {
-            // ,
-            self.write_symbol(writer)?;
-            // varName
-            self.write_identifier(writer)?;
-        }
-        // ;
-        self.write_symbol(writer)?;
-        // </classVarDec>
+        self.write_key_word(class_var_dec.kind.keyword(), writer)?;
+        self.write_type(&class_var_dec.var_type, writer)?;
+        self.write_var_names(&class_var_dec.names, writer)?;
+        self.write_symbol(';', writer)?;
         self.write_end_tag("classVarDec", writer)?;
         Ok(())
     }
 
     /// type = ’int’ | ’char’ | ’boolean’ | className
-    fn compile_type(&mut self, writer: &mut impl Write) -> Result<()> {
-        match self.tokenizer.peek()?.token_type() {
-            Keyword => self.write_key_word(vec![Int, Boolean, Char], writer)?,
-            Identifier => self.write_identifier(writer)?,
-            _ => bail!(Error::msg("Illegal token")),
+    fn write_type(&mut self, var_type: &Type, writer: &mut impl Write) -> Result<()> {
+        match var_type {
+            Type::Class(class_name) => {
+                self.write_identifier(class_name, Category::Class, false, None, writer)
+            }
+            _ => self.write_key_word(var_type.name(), writer),
         }
-        Ok(())
     }
 
-    /// subroutineDec =(’constructor’ | ’function’ | ’method’) (’void’ | type) subroutineName ’(’ parameterList ’)’ subroutineBody
-    fn compile_subroutine_dec(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <subroutineDec>
+    /// subroutineDec = (’constructor’ | ’function’ | ’method’) (’void’ | type) subroutineName ’(’ parameterList ’)’ subroutineBody
+    fn write_subroutine_dec(
+        &mut self,
+        subroutine_dec: &SubroutineDec,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        let this_type = (subroutine_dec.kind == SubroutineKind::Method).then_some(&self.class_name);
+        self.symbol_table
+            .start_subroutine(this_type.map(String::as_str));
         self.write_start_tag("subroutineDec", writer)?;
-        // ’constructor’ | ’function’ | ’method’
-        self.write_key_word(vec![Constructor, Function, Method], writer)?;
-        // ’void’ | type
-        if self.tokenizer.peek()?.token_type() == &Keyword
-            && KeyWord::from(self.tokenizer.peek()?.value())? == Void
-        {
-            self.write_key_word(vec![Void], writer)?
-        } else {
-            self.compile_type(writer)?
+        self.write_key_word(subroutine_dec.kind.keyword(), writer)?;
+        match &subroutine_dec.return_type {
+            Some(return_type) => self.write_type(return_type, writer)?,
+            None => self.write_key_word("void", writer)?,
         }
-        // subroutineName
-        self.write_identifier(writer)?;
-        // ’(’
-        self.write_symbol(writer)?;
-        // parameterList
-        self.compile_parameter_list(writer)?;
-        // ’)’
-        self.write_symbol(writer)?;
-        // subroutineBody
-        self.compile_subroutine_body(writer)?;
-        // </subroutineDec>
+        let name = &subroutine_dec.name;
+        self.write_identifier(name, Category::Subroutine, true, None, writer)?;
+        self.write_symbol('(', writer)?;
+        self.write_parameter_list(&subroutine_dec.parameters, writer)?;
+        self.write_symbol(')', writer)?;
+        self.write_subroutine_body(&subroutine_dec.body, writer)?;
         self.write_end_tag("subroutineDec", writer)?;
         Ok(())
     }
 
     /// parameterList = ((type varName) (’,’ type varName)*)?
-    fn compile_parameter_list(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <parameterList>
+    fn write_parameter_list(
+        &mut self,
+        parameters: &[Parameter],
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        self.symbol_table.define_parameters(parameters);
         self.write_start_tag("parameterList", writer)?;
-        // ((type varName) (’,’ type varName)*)?
-        if self.tokenizer.peek()?.is_type()? {
-            // type
-            self.compile_type(writer)?;
-            // varName
-            self.write_identifier(writer)?;
-            // (’,’ type varName)*
-            while self.tokenizer.peek()?.value() == "," {
-                // ’,’
-                self.write_symbol(writer)?;
-                // type
-                self.compile_type(writer)?;
-                // varName
-                self.write_identifier(writer)?;
+        for (i, parameter) in parameters.iter().enumerate() {
+            if i > 0 {
+                self.write_symbol(',', writer)?;
             }
+            self.write_type(&parameter.var_type, writer)?;
+            self.write_variable(&parameter.name, true, writer)?;
         }
-        // </parameterList>
         self.write_end_tag("parameterList", writer)?;
         Ok(())
     }
 
     /// subroutineBody = ’{’ varDec* statements ’}’
-    fn compile_subroutine_body(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <subroutineBody>
+    fn write_subroutine_body(
+        &mut self,
+        body: &SubroutineBody,
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("subroutineBody", writer)?;
-        // ’{’
-        self.write_symbol(writer)?;
-        // varDec*
-        loop {
-            if !KeyWord::exists(self.tokenizer.peek()?.value()) {
-                break;
-            }
-            match KeyWord::from(self.tokenizer.peek()?.value())? {
-                Var => self.compile_var_dec(writer)?,
-                _ => break,
-            }
+        self.write_symbol('{', writer)?;
+        for var_dec in &body.var_decs {
+            self.write_var_dec(var_dec, writer)?;
         }
-        // statements
-        self.compile_statements(writer)?;
-        // ’}’
-        self.write_symbol(writer)?;
-        // </subroutineBody>
+        self.write_statements(&body.statements, writer)?;
+        self.write_symbol('}', writer)?;
         self.write_end_tag("subroutineBody", writer)?;
         Ok(())
     }
 
     /// varDec = ’var’ type varName (’,’ varName)* ’;’
-    fn compile_var_dec(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <varDec>
+    fn write_var_dec(&mut self, var_dec: &VarDec, writer: &mut impl Write) -> Result<()> {
+        self.symbol_table.define_var_dec(var_dec);
         self.write_start_tag("varDec", writer)?;
-        // ’var’
-        self.write_key_word(vec![Var], writer)?;
-        // type
-        self.compile_type(writer)?;
-        // varName
-        self.write_identifier(writer)?;
-        // (’,’ varName)*
-        loop {
-            if self.tokenizer.peek()?.token_type() == &Symbol
-                && self.tokenizer.peek()?.value() == ","
-            {
-                // ','
-                self.write_symbol(writer)?;
-                // varName
-                self.write_identifier(writer)?;
-            } else {
-                break;
-            }
-        }
-        // ’;’
-        self.write_symbol(writer)?;
-        // </varDec>
+        self.write_key_word("var", writer)?;
+        self.write_type(&var_dec.var_type, writer)?;
+        self.write_var_names(&var_dec.names, writer)?;
+        self.write_symbol(';', writer)?;
         self.write_end_tag("varDec", writer)?;
         Ok(())
     }
 
     /// statements = statement*
-    fn compile_statements(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <statements>
+    fn write_statements(
+        &mut self,
+        statements: &[Statement],
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("statements", writer)?;
-        loop {
-            if !KeyWord::exists(self.tokenizer.peek()?.value()) {
-                break;
-            }
-            match KeyWord::from(self.tokenizer.peek()?.value())? {
-                Let | If | While | Do | Return => {
-                    self.compile_statement(writer)?;
-                }
-                _ => break,
-            }
+        for statement in statements {
+            self.write_statement(statement, writer)?;
         }
-        // </statements>
         self.write_end_tag("statements", writer)?;
         Ok(())
     }
 
     /// statement = letStatement | ifStatement | whileStatement | doStatement | returnStatement
-    fn compile_statement(&mut self, writer: &mut impl Write) -> Result<()> {
-        match KeyWord::from(self.tokenizer.peek()?.value())? {
-            Let => self.compile_let_statement(writer)?,
-            If => self.compile_if_statement(writer)?,
-            While => self.compile_while_statement(writer)?,
-            Do => self.compile_do_statement(writer)?,
-            Return => self.compile_return_statement(writer)?,
-            _ => {}
+    fn write_statement(&mut self, statement: &Statement, writer: &mut impl Write) -> Result<()> {
+        match statement {
+            Statement::Let(statement) => self.write_let_statement(statement, writer),
+            Statement::If(statement) => self.write_if_statement(statement, writer),
+            Statement::While(statement) => self.write_while_statement(statement, writer),
+            Statement::Do(statement) => self.write_do_statement(statement, writer),
+            Statement::Return(statement) => self.write_return_statement(statement, writer),
         }
-        Ok(())
     }
 
     /// letStatement = ’let’ varName (’[’ expression ’]’)? ’=’ expression ’;’
-    fn compile_let_statement(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <letStatement>
+    fn write_let_statement(
+        &mut self,
+        statement: &LetStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("letStatement", writer)?;
-        // let
-        self.write_key_word(vec![Let], writer)?;
-        // varName
-        self.write_identifier(writer)?;
-        // (’[’ expression ’]’)?
-        if self.tokenizer.peek()?.value() == "[" {
-            // ’[’
-            self.write_symbol(writer)?;
-            // expression
-            self.compile_expression(writer)?;
-            // ’]’
-            self.write_symbol(writer)?;
+        self.write_key_word("let", writer)?;
+        self.write_variable(&statement.name, false, writer)?;
+        if let Some(index) = &statement.index {
+            self.write_symbol('[', writer)?;
+            self.write_expression(index, writer)?;
+            self.write_symbol(']', writer)?;
         }
-        // ’=’
-        self.write_symbol(writer)?;
-        // expression
-        self.compile_expression(writer)?;
-        // ’;’
-        self.write_symbol(writer)?;
-        // </letStatement>
+        self.write_symbol('=', writer)?;
+        self.write_expression(&statement.value, writer)?;
+        self.write_symbol(';', writer)?;
         self.write_end_tag("letStatement", writer)?;
         Ok(())
     }
 
     /// ifStatement = ’if’ ’(’ expression ’)’ ’{’ statements ’}’ (’else’ ’{’ statements ’}’)?
-    fn compile_if_statement(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <ifStatement>
+    fn write_if_statement(
+        &mut self,
+        statement: &IfStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("ifStatement", writer)?;
-        // if
-        self.write_key_word(vec![If], writer)?;
-        // ’(’
-        self.write_symbol(writer)?;
-        // expression
-        self.compile_expression(writer)?;
-        // ’)’
-        self.write_symbol(writer)?;
-        // ’{’
-        self.write_symbol(writer)?;
-        // statements
-        self.compile_statements(writer)?;
-        // ’}’
-        self.write_symbol(writer)?;
-        // (’else’ ’{’ statements ’}’)?
-        if self.tokenizer.peek()?.token_type() == &Keyword
-            && KeyWord::from(self.tokenizer.peek()?.value())? == KeyWord::Else
-        {
-            // else
-            self.write_key_word(vec![Else], writer)?;
-            // ’{’
-            self.write_symbol(writer)?;
-            // statements
-            self.compile_statements(writer)?;
-            // ’}’
-            self.write_symbol(writer)?;
+        self.write_key_word("if", writer)?;
+        self.write_symbol('(', writer)?;
+        self.write_expression(&statement.condition, writer)?;
+        self.write_symbol(')', writer)?;
+        self.write_symbol('{', writer)?;
+        self.write_statements(&statement.statements, writer)?;
+        self.write_symbol('}', writer)?;
+        if let Some(else_statements) = &statement.else_statements {
+            self.write_key_word("else", writer)?;
+            self.write_symbol('{', writer)?;
+            self.write_statements(else_statements, writer)?;
+            self.write_symbol('}', writer)?;
         }
-        // </ifStatement>
         self.write_end_tag("ifStatement", writer)?;
         Ok(())
     }
 
     /// whileStatement = ’while’ ’(’ expression ’)’ ’{’ statements ’}’
-    fn compile_while_statement(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <whileStatement>
+    fn write_while_statement(
+        &mut self,
+        statement: &WhileStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("whileStatement", writer)?;
-        // while
-        self.write_key_word(vec![While], writer)?;
-        // ’(’
-        self.write_symbol(writer)?;
-        // expression
-        self.compile_expression(writer)?;
-        // ’)’
-        self.write_symbol(writer)?;
-        // ’{’
-        self.write_symbol(writer)?;
-        // statements
-        self.compile_statements(writer)?;
-        // ’}’
-        self.write_symbol(writer)?;
-        // </whileStatement>
+        self.write_key_word("while", writer)?;
+        self.write_symbol('(', writer)?;
+        self.write_expression(&statement.condition, writer)?;
+        self.write_symbol(')', writer)?;
+        self.write_symbol('{', writer)?;
+        self.write_statements(&statement.statements, writer)?;
+        self.write_symbol('}', writer)?;
         self.write_end_tag("whileStatement", writer)?;
         Ok(())
     }
 
     /// doStatement = ’do’ subroutineCall ’;’
-    fn compile_do_statement(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <doStatement>
+    fn write_do_statement(
+        &mut self,
+        statement: &DoStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("doStatement", writer)?;
-        // do
-        self.write_key_word(vec![Do], writer)?;
-        // subroutineCall
-        self.compile_subroutine_call(writer)?;
-        // ’;’
-        self.write_symbol(writer)?;
-        // </doStatement>
+        self.write_key_word("do", writer)?;
+        self.write_subroutine_call(&statement.call, writer)?;
+        self.write_symbol(';', writer)?;
         self.write_end_tag("doStatement", writer)?;
         Ok(())
     }
 
     /// returnStatement = ’return’ expression? ’;’
-    fn compile_return_statement(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <returnStatement>
+    fn write_return_statement(
+        &mut self,
+        statement: &ReturnStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("returnStatement", writer)?;
-        // return
-        self.write_key_word(vec![Return], writer)?;
-        // expression?
-        if self.tokenizer.peek()?.value() != ";" {
-            self.compile_expression(writer)?;
+        self.write_key_word("return", writer)?;
+        if let Some(value) = &statement.value {
+            self.write_expression(value, writer)?;
         }
-        // ’;’
-        self.write_symbol(writer)?;
-        // </returnStatement>
+        self.write_symbol(';', writer)?;
         self.write_end_tag("returnStatement", writer)?;
         Ok(())
     }
 
     /// expression = term (op term)*
-    fn compile_expression(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <expression>
+    fn write_expression(&mut self, expression: &Expression, writer: &mut impl Write) -> Result<()> {
         self.write_start_tag("expression", writer)?;
-        // term
-        self.compile_term(writer)?;
-        // (op term)*
-        loop {
-            if self.tokenizer.peek()?.is_op() {
-                // op
-                self.write_symbol(writer)?;
-                // term
-                self.compile_term(writer)?;
-            } else {
-                break;
-            }
+        self.write_term(&expression.term, writer)?;
+        for (op, term) in &expression.ops {
+            self.write_symbol(op.symbol(), writer)?;
+            self.write_term(term, writer)?;
         }
-        // </expression>
         self.write_end_tag("expression", writer)?;
         Ok(())
     }
 
     /// term = integerConstant | stringConstant | keywordConstant | varName | varName ’[’ expression ’]’ | subroutineCall | ’(’ expression ’)’ | unaryOp term
-    fn compile_term(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <term>
+    fn write_term(&mut self, term: &Term, writer: &mut impl Write) -> Result<()> {
         self.write_start_tag("term", writer)?;
-
-        match self.tokenizer.peek()?.token_type() {
-            Keyword => {
-                if self.tokenizer.peek()?.is_keyword_constant()? {
-                    self.write_key_word(vec![True, False, Null, This], writer)?;
-                }
+        match &term.kind {
+            TermKind::IntConst(value) => self.write_integer_constant(*value, writer)?,
+            TermKind::StringConst(value) => self.write_string_constant(value, writer)?,
+            TermKind::KeywordConstant(constant) => {
+                self.write_key_word(constant.keyword(), writer)?
+            }
+            TermKind::VarName(name) => self.write_variable(name, false, writer)?,
+            TermKind::ArrayAccess(name, index) => {
+                self.write_variable(name, false, writer)?;
+                self.write_symbol('[', writer)?;
+                self.write_expression(index, writer)?;
+                self.write_symbol(']', writer)?;
+            }
+            TermKind::SubroutineCall(call) => self.write_subroutine_call(call, writer)?,
+            TermKind::Parenthesized(expression) => {
+                self.write_symbol('(', writer)?;
+                self.write_expression(expression, writer)?;
+                self.write_symbol(')', writer)?;
             }
-            Symbol => match self.tokenizer.peek()?.value().as_str() {
-                "(" => {
-                    // '('
-                    self.write_symbol(writer)?;
-                    // expression
-                    self.compile_expression(writer)?;
-                    // ')'
-                    self.write_symbol(writer)?;
-                }
-                "-" | "~" => {
-                    // unaryOp
-                    self.write_symbol(writer)?;
-                    // term
-                    self.compile_term(writer)?;
-                }
-                _ => {}
-            },
-            Identifier => {
-                match self.tokenizer.peek_second()?.value().as_str() {
-                    "[" => {
-                        // varName
-                        self.write_identifier(writer)?;
-                        // '['
-                        self.write_symbol(writer)?;
-                        // expression
-                        self.compile_expression(writer)?;
-                        // ']'
-                        self.write_symbol(writer)?;
-                    }
-                    "." | "(" => self.compile_subroutine_call(writer)?,
-                    _ => self.write_identifier(writer)?,
-                }
+            TermKind::Unary(op, term) => {
+                self.write_symbol(op.symbol(), writer)?;
+                self.write_term(term, writer)?;
             }
-            IntConst => self.write_integer_constant(writer)?,
-            StringConst => self.write_string_constant(writer)?,
         }
-
-        // </term>
         self.write_end_tag("term", writer)?;
         Ok(())
     }
 
     /// subroutineCall = subroutineName ’(’ expressionList ’)’ | (className | varName) ’.’ subroutineName ’(’ expressionList ’)’
-    fn compile_subroutine_call(&mut self, writer: &mut impl Write) -> Result<()> {
-        // subroutineName | (className | varName)
-        self.write_identifier(writer)?;
-        if self.tokenizer.peek()?.value() == "." {
-            // ’.’
-            self.write_symbol(writer)?;
-            // subroutineName
-            self.write_identifier(writer)?;
+    fn write_subroutine_call(
+        &mut self,
+        call: &SubroutineCall,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        if let Some(receiver) = &call.receiver {
+            if self.symbol_table.get(&receiver.name).is_some() {
+                self.write_variable(receiver, false, writer)?;
+            } else {
+                self.write_identifier(receiver, Category::Class, false, None, writer)?;
+            }
+            self.write_symbol('.', writer)?;
         }
-        // ’(’
-        self.write_symbol(writer)?;
-        // expressionList
-        self.compile_expression_list(writer)?;
-        // ’)’
-        self.write_symbol(writer)?;
+        self.write_identifier(&call.name, Category::Subroutine, false, None, writer)?;
+        self.write_symbol('(', writer)?;
+        self.write_expression_list(&call.arguments, writer)?;
+        self.write_symbol(')', writer)?;
         Ok(())
     }
 
     /// expressionList = (expression (’,’ expression)* )?
-    fn compile_expression_list(&mut self, writer: &mut impl Write) -> Result<()> {
-        // <expressionList>
+    fn write_expression_list(
+        &mut self,
+        expressions: &[Expression],
+        writer: &mut impl Write,
+    ) -> Result<()> {
         self.write_start_tag("expressionList", writer)?;
-        // (expression)?
-        if self.tokenizer.is_term()? {
-            // expression
-            self.compile_expression(writer)?;
-            // (’,’ expression)*
-            while self.tokenizer.peek()?.value() == "," {
-                // ’,’
-                self.write_symbol(writer)?;
-                // expression
-                self.compile_expression(writer)?;
+        for (i, expression) in expressions.iter().enumerate() {
+            if i > 0 {
+                self.write_symbol(',', writer)?;
             }
+            self.write_expression(expression, writer)?;
         }
-        // </expressionList>
         self.write_end_tag("expressionList", writer)?;
         Ok(())
     }
-}
 
-impl XmlCompilationEngine {
-    fn write_key_word(&mut self, targets: Vec<KeyWord>, writer: &mut impl Write) -> Result<()> {
-        self.tokenizer.advance()?;
-        match self.tokenizer.token_type()? {
-            Keyword => {
-                let keyword = self.tokenizer.key_word()?;
-                match keyword {
-                    keyword if targets.contains(&keyword) => writeln!(
-                        writer,
-                        "{}<keyword> {} </keyword>",
-                        self.indent,
-                        self.tokenizer.key_word()?.to_string().to_lowercase()
-                    )?,
-                    _ => bail!(Error::msg("Illegal token")),
-                }
+    /// varName (’,’ varName)*
+    fn write_var_names(&mut self, names: &[Identifier], writer: &mut impl Write) -> Result<()> {
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                self.write_symbol(',', writer)?;
             }
-            _ => bail!(Error::msg("Illegal token")),
+            self.write_variable(name, true, writer)?;
         }
         Ok(())
     }
 
-    fn write_identifier(&mut self, writer: &mut impl Write) -> Result<()> {
-        self.tokenizer.advance()?;
-        match self.tokenizer.token_type()? {
-            Identifier => writeln!(
+    fn write_key_word(&mut self, keyword: &str, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "{}<keyword> {} </keyword>", self.indent, keyword)?;
+        Ok(())
+    }
+
+    /// Writes a variable name, classified by the symbol table.
+    fn write_variable(
+        &mut self,
+        identifier: &Identifier,
+        defined: bool,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        let (category, index) = match self.symbol_table.get(&identifier.name) {
+            Some(symbol) => (Category::from(symbol.kind), Some(symbol.index)),
+            None => (Category::Undefined, None),
+        };
+        self.write_identifier(identifier, category, defined, index, writer)
+    }
+
+    fn write_identifier(
+        &mut self,
+        identifier: &Identifier,
+        category: Category,
+        defined: bool,
+        index: Option<usize>,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        if !self.annotated {
+            writeln!(
                 writer,
                 "{}<identifier> {} </identifier>",
-                self.indent,
-                self.tokenizer.identifier()
-            )?,
-            _ => bail!(Error::msg("Illegal token")),
+                self.indent, identifier.name
+            )?;
+            return Ok(());
         }
+
+        let usage = if defined { "defined" } else { "used" };
+        let index = match index {
+            Some(index) => format!(" index=\"{}\"", index),
+            None => String::new(),
+        };
+        writeln!(
+            writer,
+            "{}<identifier category=\"{}\" usage=\"{}\"{}> {} </identifier>",
+            self.indent,
+            category.name(),
+            usage,
+            index,
+            identifier.name
+        )?;
         Ok(())
     }
 
-    fn write_symbol(&mut self, writer: &mut impl Write) -> Result<()> {
-        self.tokenizer.advance()?;
-        match self.tokenizer.token_type()? {
-            Symbol => {
-                let symbol = match self.tokenizer.symbol() {
-                    '<' => "&lt;",
-                    '>' => "&gt;",
-                    '&' => "&amp;",
-                    _ => "",
-                };
-
-                if symbol.is_empty() {
-                    writeln!(
-                        writer,
-                        "{}<symbol> {} </symbol>",
-                        self.indent,
-                        self.tokenizer.symbol()
-                    )?
-                } else {
-                    writeln!(writer, "{}<symbol> {} </symbol>", self.indent, symbol)?
-                }
-            }
-            _ => bail!(Error::msg("Illegal token")),
+    fn write_symbol(&mut self, symbol: char, writer: &mut impl Write) -> Result<()> {
+        let escaped = match symbol {
+            '<' => "&lt;",
+            '>' => "&gt;",
+            '&' => "&amp;",
+            _ => "",
+        };
+
+        if escaped.is_empty() {
+            writeln!(writer, "{}<symbol> {} </symbol>", self.indent, symbol)?
+        } else {
+            writeln!(writer, "{}<symbol> {} </symbol>", self.indent, escaped)?
         }
         Ok(())
     }
 
-    fn write_string_constant(&mut self, writer: &mut impl Write) -> Result<()> {
-        self.tokenizer.advance()?;
-        match self.tokenizer.token_type()? {
-            StringConst => writeln!(
-                writer,
-                "{}<stringConstant> {} </stringConstant>",
-                self.indent,
-                self.tokenizer.string_val()
-            )?,
-            _ => bail!(Error::msg("Illegal token")),
-        }
+    fn write_string_constant(&mut self, value: &str, writer: &mut impl Write) -> Result<()> {
+        writeln!(
+            writer,
+            "{}<stringConstant> {} </stringConstant>",
+            self.indent, value
+        )?;
         Ok(())
     }
 
-    fn write_integer_constant(&mut self, writer: &mut impl Write) -> Result<()> {
-        self.tokenizer.advance()?;
-        match self.tokenizer.token_type()? {
-            IntConst => writeln!(
-                writer,
-                "{}<integerConstant> {} </integerConstant>",
-                self.indent,
-                self.tokenizer.int_val()?
-            )?,
-            _ => bail!(Error::msg("Illegal token")),
-        }
+    fn write_integer_constant(&mut self, value: usize, writer: &mut impl Write) -> Result<()> {
+        writeln!(
+            writer,
+            "{}<integerConstant> {} </integerConstant>",
+            self.indent, value
+        )?;
         Ok(())
     }
 
@@ -609,10 +630,12 @@ impl XmlCompilationEngine {
 
 #[cfg(test)]
 mod tests {
-    use std::io::{Seek, SeekFrom, Write};
+    use std::str::FromStr;
 
     use crate::compilation::compilation_engine::{CompilationEngine, XmlCompilationEngine};
+    use crate::compilation::parse_error::{Expected, ParseError, ParseErrors};
     use crate::tokenizer::jack_tokenizer::JackTokenizer;
+    use crate::tokenizer::token_type::TokenType;
 
     #[test]
     fn can_compile_class() {
@@ -626,14 +649,13 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "class Main {{").unwrap();
-        writeln!(src_file, "}}").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+class Main {
+}
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_class(&mut output);
@@ -667,16 +689,15 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "class Main {{").unwrap();
-        writeln!(src_file, "static boolean test;").unwrap();
-        writeln!(src_file, "static boolean test;").unwrap();
-        writeln!(src_file, "}}").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+class Main {
+static boolean test;
+static boolean test;
+}
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_class(&mut output);
@@ -736,16 +757,15 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "class Main {{").unwrap();
-        writeln!(src_file, "function void main() {{ return; }}").unwrap();
-        writeln!(src_file, "function boolean isSomething() {{ return; }}").unwrap();
-        writeln!(src_file, "}}").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+class Main {
+function void main() { return; }
+function boolean isSomething() { return; }
+}
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_class(&mut output);
@@ -767,13 +787,12 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "static boolean test;").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+static boolean test;
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_class_var_dec(&mut output);
@@ -808,15 +827,14 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "function void main() {{").unwrap();
-        writeln!(src_file, "return;").unwrap();
-        writeln!(src_file, "}}").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+function void main() {
+return;
+}
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_subroutine_dec(&mut output);
@@ -854,17 +872,16 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "{{").unwrap();
-        writeln!(src_file, "var Array a;").unwrap();
-        writeln!(src_file, "var int length;").unwrap();
-        writeln!(src_file, "return;").unwrap();
-        writeln!(src_file, "}}").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+{
+var Array a;
+var int length;
+return;
+}
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_subroutine_body(&mut output);
@@ -890,13 +907,12 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "var int i, j, sum;").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+var int i, j, sum;
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_var_dec(&mut output);
@@ -934,17 +950,12 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(
-            src_file,
-            "let length = Keyboard.readInt(\"HOW MANY NUMBERS? \");"
-        )
-        .unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+let length = Keyboard.readInt(\"HOW MANY NUMBERS? \");
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_let_statement(&mut output);
@@ -1022,20 +1033,15 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "while (i < length) {{").unwrap();
-        writeln!(
-            src_file,
-            "let a[i] = Keyboard.readInt(\"ENTER THE NEXT NUMBER: \");"
-        )
-        .unwrap();
-        writeln!(src_file, "let i = i + 1;").unwrap();
-        writeln!(src_file, "}}").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+while (i < length) {
+let a[i] = Keyboard.readInt(\"ENTER THE NEXT NUMBER: \");
+let i = i + 1;
+}
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_while_statement(&mut output);
@@ -1067,13 +1073,12 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "do Output.printString(\"THE AVERAGE IS: \");").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+do Output.printString(\"THE AVERAGE IS: \");
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_do_statement(&mut output);
@@ -1098,13 +1103,12 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "return x;").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+return x;
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_return_statement(&mut output);
@@ -1134,13 +1138,12 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "Keyboard.readInt(\"HOW MANY NUMBERS? \")").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+Keyboard.readInt(\"HOW MANY NUMBERS? \")
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_term(&mut output);
@@ -1168,13 +1171,12 @@ mod tests {
 "
         .to_string();
 
-        let mut src_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(src_file, "Keyboard.readInt(\"HOW MANY NUMBERS? \")").unwrap();
-        src_file.seek(SeekFrom::Start(0)).unwrap();
-        let path = src_file.path();
+        let src = "\
+Keyboard.readInt(\"HOW MANY NUMBERS? \")
+";
         let mut output = Vec::<u8>::new();
 
-        let tokenizer = JackTokenizer::new(path).unwrap();
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
         let mut engine = XmlCompilationEngine::new(tokenizer);
 
         let result = engine.compile_subroutine_call(&mut output);
@@ -1183,4 +1185,148 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn can_compile_annotated_class() {
+        let expected = "\
+<class>
+  <keyword> class </keyword>
+  <identifier category=\"class\" usage=\"defined\"> Point </identifier>
+  <symbol> { </symbol>
+  <classVarDec>
+    <keyword> field </keyword>
+    <keyword> int </keyword>
+    <identifier category=\"field\" usage=\"defined\" index=\"0\"> x </identifier>
+    <symbol> ; </symbol>
+  </classVarDec>
+  <subroutineDec>
+    <keyword> method </keyword>
+    <identifier category=\"class\" usage=\"used\"> Point </identifier>
+    <identifier category=\"subroutine\" usage=\"defined\"> moved </identifier>
+    <symbol> ( </symbol>
+    <parameterList>
+      <keyword> int </keyword>
+      <identifier category=\"argument\" usage=\"defined\" index=\"1\"> dx </identifier>
+    </parameterList>
+    <symbol> ) </symbol>
+    <subroutineBody>
+      <symbol> { </symbol>
+      <varDec>
+        <keyword> var </keyword>
+        <identifier category=\"class\" usage=\"used\"> Point </identifier>
+        <identifier category=\"var\" usage=\"defined\" index=\"0\"> p </identifier>
+        <symbol> ; </symbol>
+      </varDec>
+      <statements>
+        <letStatement>
+          <keyword> let </keyword>
+          <identifier category=\"var\" usage=\"used\" index=\"0\"> p </identifier>
+          <symbol> = </symbol>
+          <expression>
+            <term>
+              <identifier category=\"class\" usage=\"used\"> Point </identifier>
+              <symbol> . </symbol>
+              <identifier category=\"subroutine\" usage=\"used\"> new </identifier>
+              <symbol> ( </symbol>
+              <expressionList>
+                <expression>
+                  <term>
+                    <identifier category=\"field\" usage=\"used\" index=\"0\"> x </identifier>
+                  </term>
+                  <symbol> + </symbol>
+                  <term>
+                    <identifier category=\"argument\" usage=\"used\" index=\"1\"> dx </identifier>
+                  </term>
+                </expression>
+              </expressionList>
+              <symbol> ) </symbol>
+            </term>
+          </expression>
+          <symbol> ; </symbol>
+        </letStatement>
+        <returnStatement>
+          <keyword> return </keyword>
+          <expression>
+            <term>
+              <identifier category=\"var\" usage=\"used\" index=\"0\"> p </identifier>
+            </term>
+          </expression>
+          <symbol> ; </symbol>
+        </returnStatement>
+      </statements>
+      <symbol> } </symbol>
+    </subroutineBody>
+  </subroutineDec>
+  <symbol> } </symbol>
+</class>
+"
+        .to_string();
+
+        let src = "\
+class Point {
+    field int x;
+    method Point moved(int dx) {
+        var Point p;
+        let p = Point.new(x + dx);
+        return p;
+    }
+}
+";
+        let mut output = Vec::<u8>::new();
+
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut engine = XmlCompilationEngine::annotated(tokenizer);
+
+        let result = engine.compile_class(&mut output);
+        let actual = String::from_utf8(output).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn let_statement_without_assignment_is_parse_error() {
+        let src = "\
+let count;
+";
+        let mut output = Vec::<u8>::new();
+
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut engine = XmlCompilationEngine::new(tokenizer);
+
+        let result = engine.compile_let_statement(&mut output);
+        let error = result.unwrap_err().downcast::<ParseError>().unwrap();
+
+        assert_eq!("letStatement", error.rule);
+        assert_eq!(
+            vec![Expected::Symbol('='), Expected::Symbol('[')],
+            error.expected
+        );
+        assert_eq!(Some((TokenType::Symbol, ";".to_string())), error.found);
+        assert_eq!((1, 10), (error.span.line, error.span.column));
+        assert_eq!("let count;", error.line_text);
+    }
+
+    #[test]
+    fn class_with_syntax_errors_writes_nothing() {
+        let src = "\
+class Main {
+    function void main() {
+        let x = ;
+        do f(;
+        return;
+    }
+}
+";
+        let mut output = Vec::<u8>::new();
+
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut engine = XmlCompilationEngine::new(tokenizer);
+
+        let result = engine.compile_class(&mut output);
+        let error = result.unwrap_err().downcast::<ParseErrors>().unwrap();
+
+        assert_eq!(2, error.errors.len());
+        assert!(output.is_empty());
+    }
 }
@@ -0,0 +1,203 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::tokenizer::jack_tokenizer::JackTokenizer;
+use crate::tokenizer::key_word::KeyWord;
+use crate::tokenizer::span::Span;
+use crate::tokenizer::token_type::TokenType;
+
+/// A kind of token the parser was looking for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    KeyWord(KeyWord),
+    Symbol(char),
+    Identifier,
+    IntConst,
+    StringConst,
+    Type,
+    Term,
+    EndOfFile,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::KeyWord(key_word) => write!(f, "'{}'", key_word.to_string().to_lowercase()),
+            Expected::Symbol(symbol) => write!(f, "'{}'", symbol),
+            Expected::Identifier => write!(f, "identifier"),
+            Expected::IntConst => write!(f, "integer constant"),
+            Expected::StringConst => write!(f, "string constant"),
+            Expected::Type => write!(f, "type"),
+            Expected::Term => write!(f, "term"),
+            Expected::EndOfFile => write!(f, "end of file"),
+        }
+    }
+}
+
+/// A syntax error: the token found where the parser expected something else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub path: PathBuf,
+    /// The grammar rule being parsed, e.g. `letStatement`.
+    pub rule: String,
+    pub expected: Vec<Expected>,
+    /// The offending token, or `None` at end of file.
+    pub found: Option<(TokenType, String)>,
+    pub span: Span,
+    /// The source line containing `span`, used to render the report.
+    pub line_text: String,
+}
+
+impl ParseError {
+    /// Builds an error for the next (not yet consumed) token of `tokenizer`.
+    pub fn unexpected(tokenizer: &JackTokenizer, rule: &str, expected: Vec<Expected>) -> Self {
+        let (found, span) = match tokenizer.peek() {
+            Ok(token) => (
+                Some((*token.token_type(), token.value().clone())),
+                *token.span(),
+            ),
+            Err(_) => (None, tokenizer.eof_span()),
+        };
+
+        ParseError {
+            path: tokenizer.path().to_path_buf(),
+            rule: rule.to_string(),
+            expected,
+            found,
+            span,
+            line_text: tokenizer.line_text(span.line).to_string(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let found = match &self.found {
+            Some((token_type, value)) => describe(token_type, value),
+            None => "end of file".to_string(),
+        };
+
+        match self.expected.as_slice() {
+            [] => format!("unexpected {}", found),
+            [expected] => format!("expected {}, found {}", expected, found),
+            [init @ .., last] => {
+                let init: Vec<String> = init.iter().map(|e| e.to_string()).collect();
+                format!("expected {} or {}, found {}", init.join(", "), last, found)
+            }
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            code: None,
+            message: self.message(),
+            path: self.path.clone(),
+            span: self.span,
+            line_text: self.line_text.clone(),
+            note: Some(format!("while parsing {}", self.rule)),
+        }
+    }
+
+    /// Renders the error with the offending source line and a caret underline.
+    pub fn report(&self) -> String {
+        self.to_diagnostic().report()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {} while parsing {}",
+            self.path.display(),
+            self.span.line,
+            self.span.column,
+            self.message(),
+            self.rule
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Every syntax error found in a class by a recovering parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrors {
+    pub errors: Vec<ParseError>,
+}
+
+impl ParseErrors {
+    /// Renders every error, separated by blank lines.
+    pub fn report(&self) -> String {
+        let reports: Vec<String> = self.errors.iter().map(|error| error.report()).collect();
+        reports.join("\n")
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self.errors.iter().map(|error| error.to_string()).collect();
+        write!(f, "{}", errors.join("\n"))
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+fn describe(token_type: &TokenType, value: &str) -> String {
+    match token_type {
+        TokenType::Keyword => format!("keyword '{}'", value),
+        TokenType::Symbol => format!("symbol '{}'", value),
+        TokenType::Identifier => format!("identifier '{}'", value),
+        TokenType::IntConst => format!("integer constant {}", value),
+        TokenType::StringConst => format!("string constant \"{}\"", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::compilation::parse_error::{Expected, ParseError};
+    use crate::tokenizer::key_word::KeyWord;
+    use crate::tokenizer::span::Span;
+    use crate::tokenizer::token_type::TokenType;
+
+    #[test]
+    fn can_render_report() {
+        let expected = "\
+error: expected '=' or '[', found symbol ';'
+  --> Main.jack:12:14
+   |
+12 |     let count;
+   |              ^
+   = while parsing letStatement
+"
+        .to_string();
+
+        let error = ParseError {
+            path: PathBuf::from("Main.jack"),
+            rule: "letStatement".to_string(),
+            expected: vec![Expected::Symbol('='), Expected::Symbol('[')],
+            found: Some((TokenType::Symbol, ";".to_string())),
+            span: Span::new(150, 151, 12, 14),
+            line_text: "    let count;".to_string(),
+        };
+
+        assert_eq!(expected, error.report());
+    }
+
+    #[test]
+    fn message_mentions_end_of_file() {
+        let error = ParseError {
+            path: PathBuf::from("Main.jack"),
+            rule: "class".to_string(),
+            expected: vec![Expected::KeyWord(KeyWord::Class)],
+            found: None,
+            span: Span::default(),
+            line_text: String::new(),
+        };
+
+        assert_eq!("expected 'class', found end of file", error.message());
+    }
+}
@@ -0,0 +1,905 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::ast::{
+    BinaryOp, Class, ClassVarDec, ClassVarKind, DoStatement, Expression, Identifier, IfStatement,
+    KeywordConstant, LetStatement, Parameter, ReturnStatement, Statement, SubroutineBody,
+    SubroutineCall, SubroutineDec, SubroutineKind, Term, TermKind, Type, UnaryOp, VarDec,
+    WhileStatement,
+};
+use crate::compilation::parse_error::{Expected, ParseError, ParseErrors};
+use crate::tokenizer::jack_tokenizer::JackTokenizer;
+use crate::tokenizer::key_word::KeyWord;
+use crate::tokenizer::span::Span;
+use crate::tokenizer::token_type::TokenType;
+
+/// Builds the `ast` of a jack class from the tokens of a `JackTokenizer`.
+pub struct Parser {
+    tokenizer: JackTokenizer,
+    rules: Vec<&'static str>,
+    /// Syntax errors recovered from while parsing a class.
+    errors: Vec<ParseError>,
+}
+
+const OPS: [char; 9] = ['+', '-', '*', '/', '&', '|', '<', '>', '='];
+
+impl Parser {
+    pub fn new(tokenizer: JackTokenizer) -> Self {
+        Parser {
+            tokenizer,
+            rules: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.tokenizer.path()
+    }
+
+    /// class = ’class’ className ’{’ classVarDec* subroutineDec* ’}’
+    ///
+    /// Recovers from syntax errors in declarations and statements, and fails with
+    /// `ParseErrors` holding all of them.
+    pub fn parse_class(&mut self) -> Result<Class> {
+        match self.parse_class_partially()? {
+            (Some(class), errors) if errors.is_empty() => Ok(class),
+            (_, errors) => bail!(ParseErrors { errors }),
+        }
+    }
+
+    /// Parses a class like `parse_class`, but returns the syntax errors along with what could be
+    /// recovered: the class without its broken declarations, unless its header or closing `}` is
+    /// broken.
+    pub fn parse_class_partially(&mut self) -> Result<(Option<Class>, Vec<ParseError>)> {
+        let class = match self.parse_class_recovering() {
+            Ok(class) => Some(class),
+            Err(error) => {
+                self.errors.push(error.downcast::<ParseError>()?);
+                None
+            }
+        };
+        Ok((class, std::mem::take(&mut self.errors)))
+    }
+
+    fn parse_class_recovering(&mut self) -> Result<Class> {
+        self.enter("class");
+        // ’class’
+        self.expect_key_word(vec![KeyWord::Class])?;
+        // className
+        let name = self.expect_identifier()?;
+        // ’{’
+        self.expect_symbol(vec!['{'])?;
+        // classVarDec*
+        let mut class_var_decs = Vec::new();
+        while matches!(self.next_key_word(), Some(KeyWord::Static | KeyWord::Field)) {
+            let class_var_dec = self.recover(Self::parse_class_var_dec, Self::synchronize_class)?;
+            if let Some(class_var_dec) = class_var_dec {
+                class_var_decs.push(class_var_dec);
+            }
+        }
+        // subroutineDec*
+        let mut subroutine_decs = Vec::new();
+        while self.next_is_subroutine_dec() {
+            let subroutine_dec =
+                self.recover(Self::parse_subroutine_dec, Self::synchronize_class)?;
+            if let Some(subroutine_dec) = subroutine_dec {
+                subroutine_decs.push(subroutine_dec);
+            }
+        }
+        // ’}’
+        self.expect_symbol(vec!['}'])?;
+        // a file holds exactly one class
+        if self.tokenizer.has_more_tokens()? {
+            let error = self.error(vec![Expected::EndOfFile]);
+            self.errors.push(error);
+        }
+        self.exit();
+        Ok(Class {
+            name,
+            class_var_decs,
+            subroutine_decs,
+        })
+    }
+
+    /// classVarDec = (’static’ | ’field’) type varName (’,’ varName)* ’;’
+    pub fn parse_class_var_dec(&mut self) -> Result<ClassVarDec> {
+        self.enter("classVarDec");
+        // ’static’ | ’field’
+        let kind = match self.expect_key_word(vec![KeyWord::Static, KeyWord::Field])? {
+            KeyWord::Static => ClassVarKind::Static,
+            _ => ClassVarKind::Field,
+        };
+        // type
+        let var_type = self.parse_type()?;
+        // varName (’,’ varName)*
+        let names = self.parse_var_names()?;
+        // ’;’
+        self.expect_symbol(vec![';'])?;
+        self.exit();
+        Ok(ClassVarDec {
+            kind,
+            var_type,
+            names,
+        })
+    }
+
+    /// type = ’int’ | ’char’ | ’boolean’ | className
+    pub fn parse_type(&mut self) -> Result<Type> {
+        match self.next_token_type() {
+            Some(TokenType::Keyword) => {
+                let targets = vec![KeyWord::Int, KeyWord::Char, KeyWord::Boolean];
+                match self.expect_key_word(targets)? {
+                    KeyWord::Int => Ok(Type::Int),
+                    KeyWord::Char => Ok(Type::Char),
+                    _ => Ok(Type::Boolean),
+                }
+            }
+            Some(TokenType::Identifier) => Ok(Type::Class(self.expect_identifier()?)),
+            _ => bail!(self.error(vec![Expected::Type])),
+        }
+    }
+
+    /// subroutineDec = (’constructor’ | ’function’ | ’method’) (’void’ | type) subroutineName ’(’ parameterList ’)’ subroutineBody
+    pub fn parse_subroutine_dec(&mut self) -> Result<SubroutineDec> {
+        self.enter("subroutineDec");
+        // ’constructor’ | ’function’ | ’method’
+        let targets = vec![KeyWord::Constructor, KeyWord::Function, KeyWord::Method];
+        let kind = match self.expect_key_word(targets)? {
+            KeyWord::Constructor => SubroutineKind::Constructor,
+            KeyWord::Function => SubroutineKind::Function,
+            _ => SubroutineKind::Method,
+        };
+        // ’void’ | type
+        let return_type = if self.next_key_word() == Some(KeyWord::Void) {
+            self.expect_key_word(vec![KeyWord::Void])?;
+            None
+        } else {
+            Some(self.parse_type()?)
+        };
+        // subroutineName
+        let name = self.expect_identifier()?;
+        // ’(’
+        self.expect_symbol(vec!['('])?;
+        // parameterList
+        let parameters = self.parse_parameter_list()?;
+        // ’)’
+        self.expect_symbol(vec![')'])?;
+        // subroutineBody
+        let body = self.parse_subroutine_body()?;
+        self.exit();
+        Ok(SubroutineDec {
+            kind,
+            return_type,
+            name,
+            parameters,
+            body,
+        })
+    }
+
+    /// parameterList = ((type varName) (’,’ type varName)*)?
+    pub fn parse_parameter_list(&mut self) -> Result<Vec<Parameter>> {
+        self.enter("parameterList");
+        let mut parameters = Vec::new();
+        if !self.next_is_symbol(')') {
+            // type varName
+            parameters.push(self.parse_parameter()?);
+            // (’,’ type varName)*
+            while self.next_is_symbol(',') {
+                self.expect_symbol(vec![','])?;
+                parameters.push(self.parse_parameter()?);
+            }
+        }
+        self.exit();
+        Ok(parameters)
+    }
+
+    /// subroutineBody = ’{’ varDec* statements ’}’
+    pub fn parse_subroutine_body(&mut self) -> Result<SubroutineBody> {
+        self.enter("subroutineBody");
+        // ’{’
+        self.expect_symbol(vec!['{'])?;
+        let errors = self.errors.len();
+        // varDec*
+        let mut var_decs = Vec::new();
+        while self.next_key_word() == Some(KeyWord::Var) {
+            let var_dec = self.recover(Self::parse_var_dec, Self::synchronize_statement)?;
+            if let Some(var_dec) = var_dec {
+                var_decs.push(var_dec);
+            }
+        }
+        // statements
+        let statements = self.parse_statements()?;
+        // ’}’, unless recovering skipped to the next subroutine, which the class goes on with
+        if self.errors.len() == errors || !self.next_is_subroutine_dec() {
+            self.expect_symbol(vec!['}'])?;
+        }
+        self.exit();
+        Ok(SubroutineBody {
+            var_decs,
+            statements,
+        })
+    }
+
+    /// varDec = ’var’ type varName (’,’ varName)* ’;’
+    pub fn parse_var_dec(&mut self) -> Result<VarDec> {
+        self.enter("varDec");
+        // ’var’
+        self.expect_key_word(vec![KeyWord::Var])?;
+        // type
+        let var_type = self.parse_type()?;
+        // varName (’,’ varName)*
+        let names = self.parse_var_names()?;
+        // ’;’
+        self.expect_symbol(vec![';'])?;
+        self.exit();
+        Ok(VarDec { var_type, names })
+    }
+
+    /// statements = statement*
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement>> {
+        self.enter("statements");
+        let mut statements = Vec::new();
+        while matches!(
+            self.next_key_word(),
+            Some(KeyWord::Let | KeyWord::If | KeyWord::While | KeyWord::Do | KeyWord::Return)
+        ) {
+            let statement = self.recover(Self::parse_statement, Self::synchronize_statement)?;
+            if let Some(statement) = statement {
+                statements.push(statement);
+            }
+        }
+        self.exit();
+        Ok(statements)
+    }
+
+    /// statement = letStatement | ifStatement | whileStatement | doStatement | returnStatement
+    pub fn parse_statement(&mut self) -> Result<Statement> {
+        match self.next_key_word() {
+            Some(KeyWord::Let) => Ok(Statement::Let(self.parse_let_statement()?)),
+            Some(KeyWord::If) => Ok(Statement::If(self.parse_if_statement()?)),
+            Some(KeyWord::While) => Ok(Statement::While(self.parse_while_statement()?)),
+            Some(KeyWord::Do) => Ok(Statement::Do(self.parse_do_statement()?)),
+            Some(KeyWord::Return) => Ok(Statement::Return(self.parse_return_statement()?)),
+            _ => bail!(self.error(
+                [
+                    KeyWord::Let,
+                    KeyWord::If,
+                    KeyWord::While,
+                    KeyWord::Do,
+                    KeyWord::Return
+                ]
+                .into_iter()
+                .map(Expected::KeyWord)
+                .collect()
+            )),
+        }
+    }
+
+    /// letStatement = ’let’ varName (’[’ expression ’]’)? ’=’ expression ’;’
+    pub fn parse_let_statement(&mut self) -> Result<LetStatement> {
+        self.enter("letStatement");
+        // ’let’
+        self.expect_key_word(vec![KeyWord::Let])?;
+        let span = self.last_span();
+        // varName
+        let name = self.expect_identifier()?;
+        // (’[’ expression ’]’)? ’=’
+        let index = if self.next_is_symbol('[') {
+            self.expect_symbol(vec!['['])?;
+            let index = self.parse_expression()?;
+            self.expect_symbol(vec![']'])?;
+            self.expect_symbol(vec!['='])?;
+            Some(Box::new(index))
+        } else {
+            self.expect_symbol(vec!['=', '['])?;
+            None
+        };
+        // expression
+        let value = self.parse_expression()?;
+        // ’;’
+        self.expect_symbol(vec![';'])?;
+        self.exit();
+        Ok(LetStatement {
+            name,
+            index,
+            value,
+            span,
+        })
+    }
+
+    /// ifStatement = ’if’ ’(’ expression ’)’ ’{’ statements ’}’ (’else’ ’{’ statements ’}’)?
+    pub fn parse_if_statement(&mut self) -> Result<IfStatement> {
+        self.enter("ifStatement");
+        // ’if’
+        self.expect_key_word(vec![KeyWord::If])?;
+        let span = self.last_span();
+        // ’(’ expression ’)’
+        self.expect_symbol(vec!['('])?;
+        let condition = self.parse_expression()?;
+        self.expect_symbol(vec![')'])?;
+        // ’{’ statements ’}’
+        self.expect_symbol(vec!['{'])?;
+        let statements = self.parse_statements()?;
+        self.expect_symbol(vec!['}'])?;
+        // (’else’ ’{’ statements ’}’)?
+        let else_statements = if self.next_key_word() == Some(KeyWord::Else) {
+            self.expect_key_word(vec![KeyWord::Else])?;
+            self.expect_symbol(vec!['{'])?;
+            let else_statements = self.parse_statements()?;
+            self.expect_symbol(vec!['}'])?;
+            Some(else_statements)
+        } else {
+            None
+        };
+        self.exit();
+        Ok(IfStatement {
+            condition,
+            statements,
+            else_statements,
+            span,
+        })
+    }
+
+    /// whileStatement = ’while’ ’(’ expression ’)’ ’{’ statements ’}’
+    pub fn parse_while_statement(&mut self) -> Result<WhileStatement> {
+        self.enter("whileStatement");
+        // ’while’
+        self.expect_key_word(vec![KeyWord::While])?;
+        let span = self.last_span();
+        // ’(’ expression ’)’
+        self.expect_symbol(vec!['('])?;
+        let condition = self.parse_expression()?;
+        self.expect_symbol(vec![')'])?;
+        // ’{’ statements ’}’
+        self.expect_symbol(vec!['{'])?;
+        let statements = self.parse_statements()?;
+        self.expect_symbol(vec!['}'])?;
+        self.exit();
+        Ok(WhileStatement {
+            condition,
+            statements,
+            span,
+        })
+    }
+
+    /// doStatement = ’do’ subroutineCall ’;’
+    pub fn parse_do_statement(&mut self) -> Result<DoStatement> {
+        self.enter("doStatement");
+        // ’do’
+        self.expect_key_word(vec![KeyWord::Do])?;
+        let span = self.last_span();
+        // subroutineCall
+        let call = self.parse_subroutine_call()?;
+        // ’;’
+        self.expect_symbol(vec![';'])?;
+        self.exit();
+        Ok(DoStatement { call, span })
+    }
+
+    /// returnStatement = ’return’ expression? ’;’
+    pub fn parse_return_statement(&mut self) -> Result<ReturnStatement> {
+        self.enter("returnStatement");
+        // ’return’
+        self.expect_key_word(vec![KeyWord::Return])?;
+        let span = self.last_span();
+        // expression?
+        let value = if self.next_is_symbol(';') {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+        // ’;’
+        self.expect_symbol(vec![';'])?;
+        self.exit();
+        Ok(ReturnStatement { value, span })
+    }
+
+    /// expression = term (op term)*
+    pub fn parse_expression(&mut self) -> Result<Expression> {
+        self.enter("expression");
+        let start = self.next_span();
+        // term
+        let term = self.parse_term()?;
+        // (op term)*
+        let mut ops = Vec::new();
+        while OPS.iter().any(|op| self.next_is_symbol(*op)) {
+            let symbol = self.expect_symbol(OPS.to_vec())?;
+            let op = BinaryOp::from(symbol).context("Illegal operator")?;
+            ops.push((op, self.parse_term()?));
+        }
+        self.exit();
+        Ok(Expression {
+            term,
+            ops,
+            span: start.to(self.last_span()),
+        })
+    }
+
+    /// term = integerConstant | stringConstant | keywordConstant | varName | varName ’[’ expression ’]’ | subroutineCall | ’(’ expression ’)’ | unaryOp term
+    pub fn parse_term(&mut self) -> Result<Term> {
+        self.enter("term");
+        let start = self.next_span();
+        let kind = match self.next_token_type() {
+            Some(TokenType::Keyword) => {
+                let targets = vec![KeyWord::True, KeyWord::False, KeyWord::Null, KeyWord::This];
+                let constant = match self.expect_key_word(targets)? {
+                    KeyWord::True => KeywordConstant::True,
+                    KeyWord::False => KeywordConstant::False,
+                    KeyWord::Null => KeywordConstant::Null,
+                    _ => KeywordConstant::This,
+                };
+                TermKind::KeywordConstant(constant)
+            }
+            Some(TokenType::Symbol) if self.next_is_symbol('(') => {
+                // ’(’ expression ’)’
+                self.expect_symbol(vec!['('])?;
+                let expression = self.parse_expression()?;
+                self.expect_symbol(vec![')'])?;
+                TermKind::Parenthesized(Box::new(expression))
+            }
+            Some(TokenType::Symbol) if self.next_is_symbol('-') || self.next_is_symbol('~') => {
+                // unaryOp term
+                let symbol = self.expect_symbol(vec!['-', '~'])?;
+                let op = UnaryOp::from(symbol).context("Illegal operator")?;
+                TermKind::Unary(op, Box::new(self.parse_term()?))
+            }
+            Some(TokenType::Identifier) => {
+                let second = self.tokenizer.peek_second().ok();
+                match second
+                    .filter(|token| token.token_type() == &TokenType::Symbol)
+                    .map(|token| token.value().as_str())
+                {
+                    Some("[") => {
+                        // varName ’[’ expression ’]’
+                        let name = self.expect_identifier()?;
+                        self.expect_symbol(vec!['['])?;
+                        let index = self.parse_expression()?;
+                        self.expect_symbol(vec![']'])?;
+                        TermKind::ArrayAccess(name, Box::new(index))
+                    }
+                    Some(".") | Some("(") => {
+                        TermKind::SubroutineCall(self.parse_subroutine_call()?)
+                    }
+                    _ => TermKind::VarName(self.expect_identifier()?),
+                }
+            }
+            Some(TokenType::IntConst) => {
+                self.expect(TokenType::IntConst, Expected::IntConst)?;
+                TermKind::IntConst(self.tokenizer.int_val())
+            }
+            Some(TokenType::StringConst) => {
+                self.expect(TokenType::StringConst, Expected::StringConst)?;
+                TermKind::StringConst(self.tokenizer.string_val().clone())
+            }
+            _ => bail!(self.error(vec![Expected::Term])),
+        };
+        self.exit();
+        Ok(Term {
+            kind,
+            span: start.to(self.last_span()),
+        })
+    }
+
+    /// subroutineCall = subroutineName ’(’ expressionList ’)’ | (className | varName) ’.’ subroutineName ’(’ expressionList ’)’
+    pub fn parse_subroutine_call(&mut self) -> Result<SubroutineCall> {
+        self.enter("subroutineCall");
+        let start = self.next_span();
+        // subroutineName | (className | varName)
+        let first = self.expect_identifier()?;
+        let (receiver, name) = if self.next_is_symbol('.') {
+            // ’.’ subroutineName
+            self.expect_symbol(vec!['.'])?;
+            (Some(first), self.expect_identifier()?)
+        } else {
+            (None, first)
+        };
+        // ’(’
+        if receiver.is_some() {
+            self.expect_symbol(vec!['('])?;
+        } else {
+            self.expect_symbol(vec!['(', '.'])?;
+        }
+        // expressionList
+        let arguments = self.parse_expression_list()?;
+        // ’)’
+        self.expect_symbol(vec![')'])?;
+        self.exit();
+        Ok(SubroutineCall {
+            receiver,
+            name,
+            arguments,
+            span: start.to(self.last_span()),
+        })
+    }
+
+    /// expressionList = (expression (’,’ expression)* )?
+    pub fn parse_expression_list(&mut self) -> Result<Vec<Expression>> {
+        self.enter("expressionList");
+        let mut expressions = Vec::new();
+        if !self.next_is_symbol(')') {
+            // expression
+            expressions.push(self.parse_expression()?);
+            // (’,’ expression)*
+            while self.next_is_symbol(',') {
+                self.expect_symbol(vec![','])?;
+                expressions.push(self.parse_expression()?);
+            }
+        }
+        self.exit();
+        Ok(expressions)
+    }
+
+    /// type varName
+    fn parse_parameter(&mut self) -> Result<Parameter> {
+        let var_type = self.parse_type()?;
+        let name = self.expect_identifier()?;
+        Ok(Parameter { var_type, name })
+    }
+
+    /// varName (’,’ varName)*
+    fn parse_var_names(&mut self) -> Result<Vec<Identifier>> {
+        let mut names = vec![self.expect_identifier()?];
+        while self.next_is_symbol(',') {
+            self.expect_symbol(vec![','])?;
+            names.push(self.expect_identifier()?);
+        }
+        Ok(names)
+    }
+
+    /// Runs `parse`. When parsing a class, a syntax error is recorded and `synchronize` skips
+    /// ahead, so that parsing continues after the broken declaration or statement.
+    fn recover<T>(
+        &mut self,
+        parse: fn(&mut Self) -> Result<T>,
+        synchronize: fn(&mut Self) -> Result<()>,
+    ) -> Result<Option<T>> {
+        let depth = self.rules.len();
+        match parse(self) {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if self.rules.first() == Some(&"class") => {
+                // the failed rules never reached `exit`
+                self.rules.truncate(depth);
+                self.errors.push(error.downcast::<ParseError>()?);
+                synchronize(self)?;
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Skips to the next class-level declaration, or to the closing `}` of the class.
+    fn synchronize_class(&mut self) -> Result<()> {
+        while self.tokenizer.peek_second().is_ok() {
+            if matches!(
+                self.next_key_word(),
+                Some(
+                    KeyWord::Static
+                        | KeyWord::Field
+                        | KeyWord::Constructor
+                        | KeyWord::Function
+                        | KeyWord::Method
+                )
+            ) {
+                return Ok(());
+            }
+            self.tokenizer.advance()?;
+        }
+        Ok(())
+    }
+
+    /// Skips past the next `;`, or to the next `}`, statement or declaration.
+    fn synchronize_statement(&mut self) -> Result<()> {
+        while self.tokenizer.has_more_tokens()? {
+            if self.next_is_symbol(';') {
+                return self.tokenizer.advance();
+            }
+            if self.next_is_symbol('}')
+                || self.next_is_subroutine_dec()
+                || matches!(
+                    self.next_key_word(),
+                    Some(
+                        KeyWord::Var
+                            | KeyWord::Let
+                            | KeyWord::If
+                            | KeyWord::While
+                            | KeyWord::Do
+                            | KeyWord::Return
+                    )
+                )
+            {
+                return Ok(());
+            }
+            self.tokenizer.advance()?;
+        }
+        Ok(())
+    }
+
+    fn enter(&mut self, rule: &'static str) {
+        self.rules.push(rule);
+    }
+
+    fn exit(&mut self) {
+        self.rules.pop();
+    }
+
+    fn expect_key_word(&mut self, targets: Vec<KeyWord>) -> Result<KeyWord> {
+        match self.next_key_word() {
+            Some(keyword) if targets.contains(&keyword) => {
+                self.tokenizer.advance()?;
+                Ok(keyword)
+            }
+            _ => bail!(self.error(targets.into_iter().map(Expected::KeyWord).collect())),
+        }
+    }
+
+    fn expect_symbol(&mut self, targets: Vec<char>) -> Result<char> {
+        match targets.iter().find(|symbol| self.next_is_symbol(**symbol)) {
+            Some(symbol) => {
+                let symbol = *symbol;
+                self.tokenizer.advance()?;
+                Ok(symbol)
+            }
+            None => bail!(self.error(targets.into_iter().map(Expected::Symbol).collect())),
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<Identifier> {
+        self.expect(TokenType::Identifier, Expected::Identifier)?;
+        Ok(Identifier {
+            name: self.tokenizer.identifier().clone(),
+            span: self.last_span(),
+        })
+    }
+
+    /// Advances over the next token if it has `token_type`, otherwise fails with `expected`.
+    fn expect(&mut self, token_type: TokenType, expected: Expected) -> Result<()> {
+        if self.next_token_type() != Some(&token_type) {
+            bail!(self.error(vec![expected]));
+        }
+        self.tokenizer.advance()
+    }
+
+    fn error(&self, expected: Vec<Expected>) -> ParseError {
+        let rule = self.rules.last().copied().unwrap_or("class");
+        ParseError::unexpected(&self.tokenizer, rule, expected)
+    }
+
+    /// The span of the token consumed last.
+    fn last_span(&self) -> Span {
+        *self.tokenizer.span()
+    }
+
+    /// The span of the next token, or of the end of file.
+    fn next_span(&self) -> Span {
+        match self.tokenizer.peek() {
+            Ok(token) => *token.span(),
+            Err(_) => self.tokenizer.eof_span(),
+        }
+    }
+
+    fn next_token_type(&self) -> Option<&TokenType> {
+        self.tokenizer.peek().ok().map(|token| token.token_type())
+    }
+
+    fn next_key_word(&self) -> Option<KeyWord> {
+        match self.tokenizer.peek() {
+            Ok(token) if token.token_type() == &TokenType::Keyword => {
+                KeyWord::from(token.value()).ok()
+            }
+            _ => None,
+        }
+    }
+
+    fn next_is_subroutine_dec(&self) -> bool {
+        matches!(
+            self.next_key_word(),
+            Some(KeyWord::Constructor | KeyWord::Function | KeyWord::Method)
+        )
+    }
+
+    fn next_is_symbol(&self, symbol: char) -> bool {
+        match self.tokenizer.peek() {
+            Ok(token) => {
+                token.token_type() == &TokenType::Symbol && token.value().starts_with(symbol)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::ast::{BinaryOp, Statement, TermKind, Type, UnaryOp};
+    use crate::compilation::parse_error::{ParseError, ParseErrors};
+    use crate::compilation::parser::Parser;
+    use crate::tokenizer::jack_tokenizer::JackTokenizer;
+    use crate::tokenizer::span::Span;
+
+    fn parser(src: &str) -> Parser {
+        Parser::new(JackTokenizer::from_str(src).unwrap())
+    }
+
+    #[test]
+    fn can_parse_class() {
+        let src = "\
+class Main {
+    field int x, y;
+    function void main(Array a, int n) {
+        var boolean done;
+        let a[n] = -x;
+        return;
+    }
+}
+";
+        let class = parser(src).parse_class().unwrap();
+
+        assert_eq!("Main", class.name.name);
+        assert_eq!(1, class.class_var_decs.len());
+        let names: Vec<&str> = class.class_var_decs[0]
+            .names
+            .iter()
+            .map(|name| name.name.as_str())
+            .collect();
+        assert_eq!(vec!["x", "y"], names);
+
+        let main = &class.subroutine_decs[0];
+        assert_eq!(None, main.return_type);
+        assert_eq!(2, main.parameters.len());
+        assert!(matches!(&main.parameters[0].var_type, Type::Class(name) if name.name == "Array"));
+        assert_eq!(Type::Int, main.parameters[1].var_type);
+        assert_eq!(Type::Boolean, main.body.var_decs[0].var_type);
+
+        let statements = &main.body.statements;
+        assert_eq!(2, statements.len());
+        match &statements[0] {
+            Statement::Let(statement) => {
+                assert_eq!("a", statement.name.name);
+                assert!(statement.index.is_some());
+                assert!(matches!(
+                    statement.value.term.kind,
+                    TermKind::Unary(UnaryOp::Neg, _)
+                ));
+            }
+            other => panic!("expected let statement, found {:?}", other),
+        }
+        assert_eq!(Span::new(131, 137, 6, 9), statements[1].span());
+    }
+
+    #[test]
+    fn expression_keeps_operations_in_source_order() {
+        let expression = parser("1 + x * Math.max(2, y)").parse_expression().unwrap();
+
+        assert_eq!(TermKind::IntConst(1), expression.term.kind);
+        let ops: Vec<BinaryOp> = expression.ops.iter().map(|(op, _)| *op).collect();
+        assert_eq!(vec![BinaryOp::Add, BinaryOp::Mul], ops);
+        match &expression.ops[1].1.kind {
+            TermKind::SubroutineCall(call) => {
+                assert_eq!("Math", call.receiver.as_ref().unwrap().name);
+                assert_eq!("max", call.name.name);
+                assert_eq!(2, call.arguments.len());
+            }
+            other => panic!("expected subroutine call, found {:?}", other),
+        }
+        assert_eq!(Span::new(0, 22, 1, 1), expression.span);
+    }
+
+    #[test]
+    fn missing_semicolon_is_parse_error() {
+        let error = parser("return x").parse_return_statement().unwrap_err();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+
+        assert_eq!("returnStatement", error.rule);
+        assert_eq!(None, error.found);
+    }
+
+    #[test]
+    fn class_reports_every_syntax_error() {
+        let src = "\
+class Main {
+    field int x
+    field int y;
+    function void main() {
+        var int a;
+        let a = ;
+        do Output.printInt(a;
+        let a = a + 1;
+        if (a) { let x = 1 }
+        return;
+    }
+    method int get() {
+        return y;
+    }
+}
+";
+        let error = parser(src).parse_class().unwrap_err();
+        let errors = &error.downcast_ref::<ParseErrors>().unwrap().errors;
+
+        let actual: Vec<(usize, usize, &str)> = errors
+            .iter()
+            .map(|error| (error.span.line, error.span.column, error.rule.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                (3, 5, "classVarDec"),
+                (6, 17, "term"),
+                (7, 29, "subroutineCall"),
+                (9, 28, "letStatement"),
+            ],
+            actual
+        );
+    }
+
+    #[test]
+    fn broken_subroutine_header_skips_to_next_subroutine() {
+        let src = "\
+class Main {
+    function void main( {
+        return;
+    }
+    function int get() {
+        return 1
+    }
+}
+";
+        let error = parser(src).parse_class().unwrap_err();
+        let errors = &error.downcast_ref::<ParseErrors>().unwrap().errors;
+
+        let lines: Vec<usize> = errors.iter().map(|error| error.span.line).collect();
+        assert_eq!(vec![2, 7], lines);
+    }
+
+    #[test]
+    fn tokens_after_the_class_are_parse_error() {
+        let error = parser("class Main { function void f() { return; } } }")
+            .parse_class()
+            .unwrap_err();
+        let errors = &error.downcast_ref::<ParseErrors>().unwrap().errors;
+
+        assert_eq!(1, errors.len());
+        assert_eq!("class", errors[0].rule);
+        assert_eq!(
+            "expected end of file, found symbol '}'",
+            errors[0].message()
+        );
+        assert_eq!(Span::new(45, 46, 1, 46), errors[0].span);
+    }
+
+    #[test]
+    fn broken_var_dec_does_not_stop_the_subroutine() {
+        let src = "\
+class Main {
+    function void main() {
+        var int a b;
+        var Array c;
+        let a = ;
+        return;
+    }
+    method int get() {
+        return (1
+    function void set() {
+        let c = 1 +;
+        return;
+    }
+}
+";
+        let error = parser(src).parse_class().unwrap_err();
+        let errors = &error.downcast_ref::<ParseErrors>().unwrap().errors;
+
+        let actual: Vec<(usize, usize, &str)> = errors
+            .iter()
+            .map(|error| (error.span.line, error.span.column, error.rule.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                (3, 19, "varDec"),
+                (5, 17, "term"),
+                (10, 5, "term"),
+                (11, 20, "term"),
+            ],
+            actual
+        );
+    }
+}
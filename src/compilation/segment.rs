@@ -0,0 +1,31 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+/// A memory segment of the Hack virtual machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    Constant,
+    Argument,
+    Local,
+    Static,
+    This,
+    That,
+    Pointer,
+    Temp,
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Segment::Constant => "constant",
+            Segment::Argument => "argument",
+            Segment::Local => "local",
+            Segment::Static => "static",
+            Segment::This => "this",
+            Segment::That => "that",
+            Segment::Pointer => "pointer",
+            Segment::Temp => "temp",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use crate::ast::{ClassVarDec, ClassVarKind, Parameter, VarDec};
+use crate::compilation::segment::Segment;
+use crate::tokenizer::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Static,
+    Field,
+    Argument,
+    Local,
+}
+
+impl SymbolKind {
+    /// The VM segment holding variables of this kind.
+    pub fn segment(&self) -> Segment {
+        match self {
+            SymbolKind::Static => Segment::Static,
+            SymbolKind::Field => Segment::This,
+            SymbolKind::Argument => Segment::Argument,
+            SymbolKind::Local => Segment::Local,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub var_type: String,
+    pub kind: SymbolKind,
+    pub index: usize,
+    /// Where the variable is declared.
+    pub span: Span,
+}
+
+/// Variables of a class scope (static, field) and of the current subroutine scope (argument, local).
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    class_scope: HashMap<String, Symbol>,
+    subroutine_scope: HashMap<String, Symbol>,
+    counts: HashMap<SymbolKind, usize>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        SymbolTable::default()
+    }
+
+    /// Starts a new subroutine scope. Pass the class name for methods to define `this` as argument 0.
+    pub fn start_subroutine(&mut self, this_type: Option<&str>) {
+        self.subroutine_scope.clear();
+        self.counts.remove(&SymbolKind::Argument);
+        self.counts.remove(&SymbolKind::Local);
+        if let Some(this_type) = this_type {
+            self.define("this", this_type, SymbolKind::Argument, Span::default());
+        }
+    }
+
+    /// Defines a variable with the next index of `kind` and returns the index.
+    pub fn define(&mut self, name: &str, var_type: &str, kind: SymbolKind, span: Span) -> usize {
+        let index = self.var_count(kind);
+        self.counts.insert(kind, index + 1);
+        let symbol = Symbol {
+            var_type: var_type.to_string(),
+            kind,
+            index,
+            span,
+        };
+        let scope = match kind {
+            SymbolKind::Static | SymbolKind::Field => &mut self.class_scope,
+            SymbolKind::Argument | SymbolKind::Local => &mut self.subroutine_scope,
+        };
+        scope.insert(name.to_string(), symbol);
+        index
+    }
+
+    pub fn define_class_var_dec(&mut self, class_var_dec: &ClassVarDec) {
+        let kind = match class_var_dec.kind {
+            ClassVarKind::Static => SymbolKind::Static,
+            ClassVarKind::Field => SymbolKind::Field,
+        };
+        for name in &class_var_dec.names {
+            self.define(&name.name, class_var_dec.var_type.name(), kind, name.span);
+        }
+    }
+
+    pub fn define_parameters(&mut self, parameters: &[Parameter]) {
+        for parameter in parameters {
+            let name = &parameter.name;
+            let var_type = parameter.var_type.name();
+            self.define(&name.name, var_type, SymbolKind::Argument, name.span);
+        }
+    }
+
+    pub fn define_var_dec(&mut self, var_dec: &VarDec) {
+        for name in &var_dec.names {
+            let var_type = var_dec.var_type.name();
+            self.define(&name.name, var_type, SymbolKind::Local, name.span);
+        }
+    }
+
+    pub fn var_count(&self, kind: SymbolKind) -> usize {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Looks up `name` only in the scope that variables of `kind` are defined in.
+    pub fn get_in_scope_of(&self, kind: SymbolKind, name: &str) -> Option<&Symbol> {
+        match kind {
+            SymbolKind::Static | SymbolKind::Field => self.class_scope.get(name),
+            SymbolKind::Argument | SymbolKind::Local => self.subroutine_scope.get(name),
+        }
+    }
+
+    /// Looks up `name` in the subroutine scope first, then in the class scope.
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.subroutine_scope
+            .get(name)
+            .or_else(|| self.class_scope.get(name))
+    }
+
+    pub fn kind_of(&self, name: &str) -> Option<SymbolKind> {
+        self.get(name).map(|symbol| symbol.kind)
+    }
+
+    pub fn type_of(&self, name: &str) -> Option<&str> {
+        self.get(name).map(|symbol| symbol.var_type.as_str())
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.get(name).map(|symbol| symbol.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compilation::symbol_table::{SymbolKind, SymbolTable};
+    use crate::tokenizer::span::Span;
+
+    #[test]
+    fn indices_run_per_kind() {
+        let mut table = SymbolTable::new();
+        table.define("x", "int", SymbolKind::Field, Span::default());
+        table.define("count", "int", SymbolKind::Static, Span::default());
+        table.define("y", "int", SymbolKind::Field, Span::default());
+
+        assert_eq!(Some(0), table.index_of("x"));
+        assert_eq!(Some(1), table.index_of("y"));
+        assert_eq!(Some(0), table.index_of("count"));
+        assert_eq!(2, table.var_count(SymbolKind::Field));
+        assert_eq!(1, table.var_count(SymbolKind::Static));
+    }
+
+    #[test]
+    fn method_scope_starts_with_this() {
+        let mut table = SymbolTable::new();
+        table.define("x", "int", SymbolKind::Field, Span::default());
+        table.start_subroutine(Some("Point"));
+        table.define("other", "Point", SymbolKind::Argument, Span::default());
+        table.define("x", "boolean", SymbolKind::Local, Span::default());
+
+        assert_eq!(Some("Point"), table.type_of("this"));
+        assert_eq!(Some(0), table.index_of("this"));
+        assert_eq!(Some(1), table.index_of("other"));
+        // the local shadows the field
+        assert_eq!(Some(SymbolKind::Local), table.kind_of("x"));
+        assert_eq!(Some("boolean"), table.type_of("x"));
+    }
+
+    #[test]
+    fn start_subroutine_clears_subroutine_scope() {
+        let mut table = SymbolTable::new();
+        table.define("x", "int", SymbolKind::Field, Span::default());
+        table.start_subroutine(None);
+        table.define("a", "int", SymbolKind::Argument, Span::default());
+        table.define("i", "int", SymbolKind::Local, Span::default());
+        table.start_subroutine(None);
+
+        assert_eq!(None, table.get("a"));
+        assert_eq!(None, table.get("i"));
+        assert_eq!(0, table.var_count(SymbolKind::Argument));
+        assert_eq!(0, table.var_count(SymbolKind::Local));
+        assert_eq!(Some(SymbolKind::Field), table.kind_of("x"));
+    }
+}
@@ -0,0 +1,779 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::Write;
+
+use anyhow::{bail, Result};
+
+use crate::ast::{
+    BinaryOp, Class, DoStatement, Expression, Identifier, IfStatement, KeywordConstant,
+    LetStatement, ReturnStatement, Statement, SubroutineBody, SubroutineCall, SubroutineDec,
+    SubroutineKind, Term, TermKind, UnaryOp, WhileStatement,
+};
+use crate::compilation::compilation_engine::CompilationEngine;
+use crate::compilation::parser::Parser;
+use crate::compilation::segment::Segment;
+use crate::compilation::symbol_table::{Symbol, SymbolKind, SymbolTable};
+use crate::tokenizer::jack_tokenizer::JackTokenizer;
+
+/// Parses with `Parser` and writes Hack VM code (nand2tetris project 11).
+pub struct VmCompilationEngine {
+    parser: Parser,
+    class_name: String,
+    symbol_table: SymbolTable,
+    if_count: usize,
+    while_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArithmeticCommand {
+    Add,
+    Sub,
+    Neg,
+    Eq,
+    Gt,
+    Lt,
+    And,
+    Or,
+    Not,
+}
+
+impl fmt::Display for ArithmeticCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ArithmeticCommand::Add => "add",
+            ArithmeticCommand::Sub => "sub",
+            ArithmeticCommand::Neg => "neg",
+            ArithmeticCommand::Eq => "eq",
+            ArithmeticCommand::Gt => "gt",
+            ArithmeticCommand::Lt => "lt",
+            ArithmeticCommand::And => "and",
+            ArithmeticCommand::Or => "or",
+            ArithmeticCommand::Not => "not",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl CompilationEngine for VmCompilationEngine {
+    fn new(tokenizer: JackTokenizer) -> Self {
+        VmCompilationEngine {
+            parser: Parser::new(tokenizer),
+            class_name: String::new(),
+            symbol_table: SymbolTable::new(),
+            if_count: 0,
+            while_count: 0,
+        }
+    }
+
+    fn compile_class(&mut self, writer: &mut impl Write) -> Result<()> {
+        let class = self.parser.parse_class()?;
+        self.write_class(&class, writer)
+    }
+
+    fn compile_class_var_dec(&mut self, _writer: &mut impl Write) -> Result<()> {
+        let class_var_dec = self.parser.parse_class_var_dec()?;
+        self.symbol_table.define_class_var_dec(&class_var_dec);
+        Ok(())
+    }
+
+    fn compile_type(&mut self, _writer: &mut impl Write) -> Result<()> {
+        self.parser.parse_type()?;
+        Ok(())
+    }
+
+    fn compile_subroutine_dec(&mut self, writer: &mut impl Write) -> Result<()> {
+        let subroutine_dec = self.parser.parse_subroutine_dec()?;
+        self.write_subroutine_dec(&subroutine_dec, writer)
+    }
+
+    fn compile_parameter_list(&mut self, _writer: &mut impl Write) -> Result<()> {
+        let parameters = self.parser.parse_parameter_list()?;
+        self.symbol_table.define_parameters(&parameters);
+        Ok(())
+    }
+
+    fn compile_subroutine_body(&mut self, writer: &mut impl Write) -> Result<()> {
+        let body = self.parser.parse_subroutine_body()?;
+        for var_dec in &body.var_decs {
+            self.symbol_table.define_var_dec(var_dec);
+        }
+        self.write_statements(&body.statements, writer)
+    }
+
+    fn compile_var_dec(&mut self, _writer: &mut impl Write) -> Result<()> {
+        let var_dec = self.parser.parse_var_dec()?;
+        self.symbol_table.define_var_dec(&var_dec);
+        Ok(())
+    }
+
+    fn compile_statements(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statements = self.parser.parse_statements()?;
+        self.write_statements(&statements, writer)
+    }
+
+    fn compile_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_statement()?;
+        self.write_statement(&statement, writer)
+    }
+
+    fn compile_let_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_let_statement()?;
+        self.write_let_statement(&statement, writer)
+    }
+
+    fn compile_if_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_if_statement()?;
+        self.write_if_statement(&statement, writer)
+    }
+
+    fn compile_while_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_while_statement()?;
+        self.write_while_statement(&statement, writer)
+    }
+
+    fn compile_do_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_do_statement()?;
+        self.write_do_statement(&statement, writer)
+    }
+
+    fn compile_return_statement(&mut self, writer: &mut impl Write) -> Result<()> {
+        let statement = self.parser.parse_return_statement()?;
+        self.write_return_statement(&statement, writer)
+    }
+
+    fn compile_expression(&mut self, writer: &mut impl Write) -> Result<()> {
+        let expression = self.parser.parse_expression()?;
+        self.write_expression(&expression, writer)
+    }
+
+    fn compile_term(&mut self, writer: &mut impl Write) -> Result<()> {
+        let term = self.parser.parse_term()?;
+        self.write_term(&term, writer)
+    }
+
+    fn compile_subroutine_call(&mut self, writer: &mut impl Write) -> Result<()> {
+        let call = self.parser.parse_subroutine_call()?;
+        self.write_subroutine_call(&call, writer)
+    }
+
+    fn compile_expression_list(&mut self, writer: &mut impl Write) -> Result<()> {
+        let expressions = self.parser.parse_expression_list()?;
+        for expression in &expressions {
+            self.write_expression(expression, writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl VmCompilationEngine {
+    /// The variables of the class and of the subroutine compiled last.
+    pub fn symbol_table(&self) -> &SymbolTable {
+        &self.symbol_table
+    }
+
+    fn write_class(&mut self, class: &Class, writer: &mut impl Write) -> Result<()> {
+        self.class_name = class.name.name.clone();
+        for class_var_dec in &class.class_var_decs {
+            self.symbol_table.define_class_var_dec(class_var_dec);
+        }
+        for subroutine_dec in &class.subroutine_decs {
+            self.write_subroutine_dec(subroutine_dec, writer)?;
+        }
+        Ok(())
+    }
+
+    fn write_subroutine_dec(
+        &mut self,
+        subroutine_dec: &SubroutineDec,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        self.start_subroutine(subroutine_dec.kind);
+        self.symbol_table
+            .define_parameters(&subroutine_dec.parameters);
+        self.write_subroutine_body(subroutine_dec, &subroutine_dec.body, writer)
+    }
+
+    fn write_subroutine_body(
+        &mut self,
+        subroutine_dec: &SubroutineDec,
+        body: &SubroutineBody,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        for var_dec in &body.var_decs {
+            self.symbol_table.define_var_dec(var_dec);
+        }
+        let name = format!("{}.{}", self.class_name, subroutine_dec.name.name);
+        let local_count = self.symbol_table.var_count(SymbolKind::Local);
+        self.write_function(&name, local_count, writer)?;
+        match subroutine_dec.kind {
+            SubroutineKind::Constructor => {
+                let field_count = self.symbol_table.var_count(SymbolKind::Field);
+                self.write_push(Segment::Constant, field_count, writer)?;
+                self.write_call("Memory.alloc", 1, writer)?;
+                self.write_pop(Segment::Pointer, 0, writer)?;
+            }
+            SubroutineKind::Method => {
+                self.write_push(Segment::Argument, 0, writer)?;
+                self.write_pop(Segment::Pointer, 0, writer)?;
+            }
+            SubroutineKind::Function => {}
+        }
+        self.write_statements(&body.statements, writer)
+    }
+
+    fn write_statements(
+        &mut self,
+        statements: &[Statement],
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        for statement in statements {
+            self.write_statement(statement, writer)?;
+        }
+        Ok(())
+    }
+
+    fn write_statement(&mut self, statement: &Statement, writer: &mut impl Write) -> Result<()> {
+        match statement {
+            Statement::Let(statement) => self.write_let_statement(statement, writer),
+            Statement::If(statement) => self.write_if_statement(statement, writer),
+            Statement::While(statement) => self.write_while_statement(statement, writer),
+            Statement::Do(statement) => self.write_do_statement(statement, writer),
+            Statement::Return(statement) => self.write_return_statement(statement, writer),
+        }
+    }
+
+    fn write_let_statement(
+        &mut self,
+        statement: &LetStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        let variable = self.variable(&statement.name)?;
+        match &statement.index {
+            Some(index) => {
+                // the target address is computed before the value, which may itself use `that`
+                self.write_expression(index, writer)?;
+                self.write_push(variable.kind.segment(), variable.index, writer)?;
+                self.write_arithmetic(ArithmeticCommand::Add, writer)?;
+                self.write_expression(&statement.value, writer)?;
+                self.write_pop(Segment::Temp, 0, writer)?;
+                self.write_pop(Segment::Pointer, 1, writer)?;
+                self.write_push(Segment::Temp, 0, writer)?;
+                self.write_pop(Segment::That, 0, writer)?;
+            }
+            None => {
+                self.write_expression(&statement.value, writer)?;
+                self.write_pop(variable.kind.segment(), variable.index, writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_if_statement(
+        &mut self,
+        statement: &IfStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        let count = self.if_count;
+        self.if_count += 1;
+        let if_true = format!("IF_TRUE{}", count);
+        let if_false = format!("IF_FALSE{}", count);
+        let if_end = format!("IF_END{}", count);
+
+        self.write_expression(&statement.condition, writer)?;
+        self.write_if(&if_true, writer)?;
+        self.write_goto(&if_false, writer)?;
+        self.write_label(&if_true, writer)?;
+        self.write_statements(&statement.statements, writer)?;
+        match &statement.else_statements {
+            Some(else_statements) => {
+                self.write_goto(&if_end, writer)?;
+                self.write_label(&if_false, writer)?;
+                self.write_statements(else_statements, writer)?;
+                self.write_label(&if_end, writer)?;
+            }
+            None => self.write_label(&if_false, writer)?,
+        }
+        Ok(())
+    }
+
+    fn write_while_statement(
+        &mut self,
+        statement: &WhileStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        let count = self.while_count;
+        self.while_count += 1;
+        let while_exp = format!("WHILE_EXP{}", count);
+        let while_end = format!("WHILE_END{}", count);
+
+        self.write_label(&while_exp, writer)?;
+        self.write_expression(&statement.condition, writer)?;
+        self.write_arithmetic(ArithmeticCommand::Not, writer)?;
+        self.write_if(&while_end, writer)?;
+        self.write_statements(&statement.statements, writer)?;
+        self.write_goto(&while_exp, writer)?;
+        self.write_label(&while_end, writer)?;
+        Ok(())
+    }
+
+    fn write_do_statement(
+        &mut self,
+        statement: &DoStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        self.write_subroutine_call(&statement.call, writer)?;
+        // discard the returned value
+        self.write_pop(Segment::Temp, 0, writer)?;
+        Ok(())
+    }
+
+    fn write_return_statement(
+        &mut self,
+        statement: &ReturnStatement,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        match &statement.value {
+            Some(value) => self.write_expression(value, writer)?,
+            // void subroutines return 0
+            None => self.write_push(Segment::Constant, 0, writer)?,
+        }
+        self.write_return(writer)
+    }
+
+    fn write_expression(&mut self, expression: &Expression, writer: &mut impl Write) -> Result<()> {
+        self.write_term(&expression.term, writer)?;
+        for (op, term) in &expression.ops {
+            self.write_term(term, writer)?;
+            self.write_binary_op(*op, writer)?;
+        }
+        Ok(())
+    }
+
+    fn write_term(&mut self, term: &Term, writer: &mut impl Write) -> Result<()> {
+        match &term.kind {
+            TermKind::IntConst(value) => self.write_int_const(*value, writer)?,
+            TermKind::StringConst(value) => {
+                self.write_push(Segment::Constant, value.chars().count(), writer)?;
+                self.write_call("String.new", 1, writer)?;
+                for c in value.chars() {
+                    self.write_push(Segment::Constant, c as usize, writer)?;
+                    self.write_call("String.appendChar", 2, writer)?;
+                }
+            }
+            TermKind::KeywordConstant(constant) => match constant {
+                KeywordConstant::True => {
+                    self.write_push(Segment::Constant, 0, writer)?;
+                    self.write_arithmetic(ArithmeticCommand::Not, writer)?;
+                }
+                KeywordConstant::False | KeywordConstant::Null => {
+                    self.write_push(Segment::Constant, 0, writer)?
+                }
+                KeywordConstant::This => self.write_push(Segment::Pointer, 0, writer)?,
+            },
+            TermKind::VarName(name) => {
+                let variable = self.variable(name)?;
+                self.write_push(variable.kind.segment(), variable.index, writer)?;
+            }
+            TermKind::ArrayAccess(name, index) => {
+                let variable = self.variable(name)?;
+                self.write_expression(index, writer)?;
+                self.write_push(variable.kind.segment(), variable.index, writer)?;
+                self.write_arithmetic(ArithmeticCommand::Add, writer)?;
+                self.write_pop(Segment::Pointer, 1, writer)?;
+                self.write_push(Segment::That, 0, writer)?;
+            }
+            TermKind::SubroutineCall(call) => self.write_subroutine_call(call, writer)?,
+            TermKind::Parenthesized(expression) => self.write_expression(expression, writer)?,
+            TermKind::Unary(op, term) => {
+                self.write_term(term, writer)?;
+                let command = match op {
+                    UnaryOp::Neg => ArithmeticCommand::Neg,
+                    UnaryOp::Not => ArithmeticCommand::Not,
+                };
+                self.write_arithmetic(command, writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_subroutine_call(
+        &mut self,
+        call: &SubroutineCall,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        let (class_name, receiver_count) = match &call.receiver {
+            // a method of the current object
+            None => {
+                self.write_push(Segment::Pointer, 0, writer)?;
+                (self.class_name.clone(), 1)
+            }
+            Some(receiver) => match self.symbol_table.get(&receiver.name).cloned() {
+                // a method of the object held by the variable
+                Some(variable) => {
+                    self.write_push(variable.kind.segment(), variable.index, writer)?;
+                    (variable.var_type, 1)
+                }
+                // a function or constructor of the class
+                None => (receiver.name.clone(), 0),
+            },
+        };
+        for argument in &call.arguments {
+            self.write_expression(argument, writer)?;
+        }
+        let name = format!("{}.{}", class_name, call.name.name);
+        self.write_call(&name, call.arguments.len() + receiver_count, writer)
+    }
+
+    fn write_binary_op(&mut self, op: BinaryOp, writer: &mut impl Write) -> Result<()> {
+        let command = match op {
+            BinaryOp::Add => ArithmeticCommand::Add,
+            BinaryOp::Sub => ArithmeticCommand::Sub,
+            BinaryOp::Mul => return self.write_call("Math.multiply", 2, writer),
+            BinaryOp::Div => return self.write_call("Math.divide", 2, writer),
+            BinaryOp::And => ArithmeticCommand::And,
+            BinaryOp::Or => ArithmeticCommand::Or,
+            BinaryOp::Lt => ArithmeticCommand::Lt,
+            BinaryOp::Gt => ArithmeticCommand::Gt,
+            BinaryOp::Eq => ArithmeticCommand::Eq,
+        };
+        self.write_arithmetic(command, writer)
+    }
+
+    fn start_subroutine(&mut self, kind: SubroutineKind) {
+        let this_type = (kind == SubroutineKind::Method).then_some(self.class_name.as_str());
+        self.symbol_table.start_subroutine(this_type);
+        self.if_count = 0;
+        self.while_count = 0;
+    }
+
+    fn variable(&self, identifier: &Identifier) -> Result<Symbol> {
+        match self.symbol_table.get(&identifier.name) {
+            Some(symbol) => Ok(symbol.clone()),
+            None => bail!(
+                "{}:{}:{}: undefined variable `{}`",
+                self.parser.path().display(),
+                identifier.span.line,
+                identifier.span.column,
+                identifier.name
+            ),
+        }
+    }
+
+    /// Pushes the 16-bit value `value` wraps to. Only 0..=32767 can be pushed as a constant, which
+    /// larger values allowed by `allow_int_overflow` are lowered to.
+    fn write_int_const(&mut self, value: usize, writer: &mut impl Write) -> Result<()> {
+        match value % 65536 {
+            value @ 0..=32767 => self.write_push(Segment::Constant, value, writer),
+            // -32768
+            32768 => {
+                self.write_push(Segment::Constant, 32767, writer)?;
+                self.write_arithmetic(ArithmeticCommand::Neg, writer)?;
+                self.write_push(Segment::Constant, 1, writer)?;
+                self.write_arithmetic(ArithmeticCommand::Sub, writer)
+            }
+            value => {
+                self.write_push(Segment::Constant, 65536 - value, writer)?;
+                self.write_arithmetic(ArithmeticCommand::Neg, writer)
+            }
+        }
+    }
+
+    fn write_push(
+        &mut self,
+        segment: Segment,
+        index: usize,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        writeln!(writer, "push {} {}", segment, index)?;
+        Ok(())
+    }
+
+    fn write_pop(&mut self, segment: Segment, index: usize, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "pop {} {}", segment, index)?;
+        Ok(())
+    }
+
+    fn write_arithmetic(
+        &mut self,
+        command: ArithmeticCommand,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        writeln!(writer, "{}", command)?;
+        Ok(())
+    }
+
+    fn write_label(&mut self, label: &str, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "label {}", label)?;
+        Ok(())
+    }
+
+    fn write_goto(&mut self, label: &str, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "goto {}", label)?;
+        Ok(())
+    }
+
+    fn write_if(&mut self, label: &str, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "if-goto {}", label)?;
+        Ok(())
+    }
+
+    fn write_call(&mut self, name: &str, arg_count: usize, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "call {} {}", name, arg_count)?;
+        Ok(())
+    }
+
+    fn write_function(
+        &mut self,
+        name: &str,
+        local_count: usize,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        writeln!(writer, "function {} {}", name, local_count)?;
+        Ok(())
+    }
+
+    fn write_return(&mut self, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "return")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::compilation::compilation_engine::CompilationEngine;
+    use crate::compilation::symbol_table::SymbolKind;
+    use crate::compilation::vm_compilation_engine::VmCompilationEngine;
+    use crate::tokenizer::jack_tokenizer::JackTokenizer;
+    use crate::tokenizer::tokenizer_options::TokenizerOptions;
+
+    fn compile(src: &str) -> String {
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut engine = VmCompilationEngine::new(tokenizer);
+        let mut output = Vec::<u8>::new();
+        engine.compile_class(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn can_compile_constructor_and_method() {
+        let expected = "\
+function Point.new 0
+push constant 2
+call Memory.alloc 1
+pop pointer 0
+push argument 0
+pop this 0
+push argument 1
+pop this 1
+push pointer 0
+return
+function Point.sum 1
+push argument 0
+pop pointer 0
+push this 0
+push this 1
+add
+pop local 0
+push local 0
+return
+function Point.origin 0
+push constant 0
+push constant 0
+call Point.new 2
+return
+";
+        let src = "\
+class Point {
+    field int x, y;
+    constructor Point new(int ax, int ay) {
+        let x = ax;
+        let y = ay;
+        return this;
+    }
+    method int sum() {
+        var int s;
+        let s = x + y;
+        return s;
+    }
+    function Point origin() {
+        return Point.new(0, 0);
+    }
+}
+";
+        assert_eq!(expected, compile(src));
+    }
+
+    #[test]
+    fn can_compile_control_flow() {
+        let expected = "\
+function Main.main 1
+label WHILE_EXP0
+push local 0
+push constant 10
+lt
+not
+if-goto WHILE_END0
+push local 0
+push constant 5
+eq
+if-goto IF_TRUE0
+goto IF_FALSE0
+label IF_TRUE0
+push constant 0
+not
+pop static 0
+goto IF_END0
+label IF_FALSE0
+push local 0
+push constant 1
+add
+pop local 0
+label IF_END0
+goto WHILE_EXP0
+label WHILE_END0
+push constant 0
+return
+";
+        let src = "\
+class Main {
+    static boolean found;
+    function void main() {
+        var int i;
+        while (i < 10) {
+            if (i = 5) {
+                let found = true;
+            } else {
+                let i = i + 1;
+            }
+        }
+        return;
+    }
+}
+";
+        assert_eq!(expected, compile(src));
+    }
+
+    #[test]
+    fn can_compile_arrays_strings_and_calls() {
+        let expected = "\
+function Main.main 1
+push constant 1
+push local 0
+add
+push constant 2
+call String.new 1
+push constant 104
+call String.appendChar 2
+push constant 105
+call String.appendChar 2
+pop temp 0
+pop pointer 1
+push temp 0
+pop that 0
+push constant 0
+push local 0
+add
+pop pointer 1
+push that 0
+call Output.printString 1
+pop temp 0
+push pointer 0
+push constant 3
+neg
+call Main.draw 2
+pop temp 0
+push constant 0
+return
+";
+        let src = "\
+class Main {
+    function void main() {
+        var Array a;
+        let a[1] = \"hi\";
+        do Output.printString(a[0]);
+        do draw(-3);
+        return;
+    }
+}
+";
+        assert_eq!(expected, compile(src));
+    }
+
+    #[test]
+    fn integer_constants_above_32767_wrap_to_16_bits() {
+        let expected = "\
+function Main.main 0
+push constant 25536
+neg
+push constant 32767
+neg
+push constant 1
+sub
+add
+push constant 1
+add
+return
+";
+        let src = "\
+class Main {
+    function int main() {
+        return 40000 + 32768 + 65537;
+    }
+}
+";
+        let options = TokenizerOptions {
+            allow_int_overflow: true,
+        };
+        let tokenizer = JackTokenizer::from_source(src, None, options).unwrap();
+        let mut engine = VmCompilationEngine::new(tokenizer);
+        let mut output = Vec::<u8>::new();
+        engine.compile_class(&mut output).unwrap();
+
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn symbol_table_is_queryable_after_compilation() {
+        let src = "\
+class Point {
+    field int x;
+    static Point origin;
+    method int getX(int scale) {
+        var int result;
+        return x;
+    }
+}
+";
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut engine = VmCompilationEngine::new(tokenizer);
+        engine.compile_class(&mut Vec::<u8>::new()).unwrap();
+        let table = engine.symbol_table();
+
+        assert_eq!(Some(SymbolKind::Field), table.kind_of("x"));
+        assert_eq!(Some("Point"), table.type_of("origin"));
+        assert_eq!(Some(1), table.index_of("scale"));
+        assert_eq!(Some(SymbolKind::Local), table.kind_of("result"));
+    }
+
+    #[test]
+    fn undefined_variable_is_error() {
+        let src = "\
+class Main {
+    function void main() {
+        let x = 1;
+        return;
+    }
+}
+";
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut engine = VmCompilationEngine::new(tokenizer);
+        let result = engine.compile_class(&mut Vec::<u8>::new());
+
+        assert_eq!(
+            "<source>:3:13: undefined variable `x`",
+            result.unwrap_err().to_string()
+        );
+    }
+}
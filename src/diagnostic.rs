@@ -0,0 +1,205 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+
+use crate::tokenizer::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A message attached to a location in a jack file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The lint that reported the diagnostic, shown as `warning[code]`.
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub path: PathBuf,
+    pub span: Span,
+    /// The source line containing `span`, used to render the report.
+    pub line_text: String,
+    pub note: Option<String>,
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic with the offending source line and a caret underline.
+    pub fn report(&self) -> String {
+        let line_number = self.span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let line_text = self.line_text.trim_end();
+        let before: String = line_text.chars().take(self.span.column - 1).collect();
+        let padding: String = before
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let rest = &line_text[before.len()..];
+        let width = rest
+            .char_indices()
+            .take_while(|(i, _)| *i < self.span.end - self.span.start)
+            .count();
+
+        let mut report = format!(
+            "{}: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self.header(),
+            self.message,
+            gutter,
+            self.path.display(),
+            self.span.line,
+            self.span.column,
+            gutter,
+            line_number,
+            line_text,
+            gutter,
+            padding,
+            "^".repeat(width.max(1)),
+        );
+        if let Some(note) = &self.note {
+            report += &format!("{} = {}\n", gutter, note);
+        }
+        report
+    }
+
+    /// Renders the diagnostic as a single-line JSON object for tools.
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<&str>| value.map_or("null".to_string(), json_string);
+        format!(
+            "{{\"severity\":\"{}\",\"code\":{},\"message\":{},\"path\":{},\"line\":{},\"column\":{},\"start\":{},\"end\":{},\"note\":{}}}",
+            self.severity,
+            optional(self.code),
+            json_string(&self.message),
+            json_string(&self.path.to_string_lossy()),
+            self.span.line,
+            self.span.column,
+            self.span.start,
+            self.span.end,
+            optional(self.note.as_deref()),
+        )
+    }
+
+    fn header(&self) -> String {
+        match self.code {
+            Some(code) => format!("{}[{}]", self.severity, code),
+            None => self.severity.to_string(),
+        }
+    }
+}
+
+/// Renders an error that has no location in a jack file as a JSON object with the fields of
+/// `Diagnostic::to_json`, where the location is null. `path` is the file it concerns, if any.
+pub fn error_to_json(message: &str, path: Option<&Path>) -> String {
+    let path = path.map_or("null".to_string(), |path| {
+        json_string(&path.to_string_lossy())
+    });
+    format!(
+        "{{\"severity\":\"error\",\"code\":null,\"message\":{},\"path\":{},\"line\":null,\"column\":null,\"start\":null,\"end\":null,\"note\":null}}",
+        json_string(message),
+        path,
+    )
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            '\n' => json += "\\n",
+            '\r' => json += "\\r",
+            '\t' => json += "\\t",
+            c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}: {}",
+            self.path.display(),
+            self.span.line,
+            self.span.column,
+            self.header(),
+            self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::diagnostic::{error_to_json, Diagnostic, Severity};
+    use crate::tokenizer::span::Span;
+
+    #[test]
+    fn can_render_report() {
+        let expected = "\
+warning: unused variable `count`
+  --> Main.jack:12:13
+   |
+12 |     var int count;
+   |             ^^^^^
+   = declared here
+"
+        .to_string();
+
+        let diagnostic = Diagnostic {
+            severity: Severity::Warning,
+            code: None,
+            message: "unused variable `count`".to_string(),
+            path: PathBuf::from("Main.jack"),
+            span: Span::new(150, 155, 12, 13),
+            line_text: "    var int count;".to_string(),
+            note: Some("declared here".to_string()),
+        };
+
+        assert_eq!(expected, diagnostic.report());
+    }
+
+    #[test]
+    fn can_render_json() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Warning,
+            code: Some("unused-variable"),
+            message: "unused variable `count`".to_string(),
+            path: PathBuf::from("Main.jack"),
+            span: Span::new(150, 155, 12, 13),
+            line_text: "    var int count;".to_string(),
+            note: Some("say \"hi\"\n".to_string()),
+        };
+
+        assert_eq!(
+            "{\"severity\":\"warning\",\"code\":\"unused-variable\",\"message\":\"unused variable `count`\",\"path\":\"Main.jack\",\"line\":12,\"column\":13,\"start\":150,\"end\":155,\"note\":\"say \\\"hi\\\"\\n\"}",
+            diagnostic.to_json()
+        );
+        assert_eq!(
+            "Main.jack:12:13: warning[unused-variable]: unused variable `count`",
+            diagnostic.to_string()
+        );
+    }
+
+    #[test]
+    fn can_render_error_without_location_as_json() {
+        assert_eq!(
+            "{\"severity\":\"error\",\"code\":null,\"message\":\"no such file\",\"path\":\"Main.jack\",\"line\":null,\"column\":null,\"start\":null,\"end\":null,\"note\":null}",
+            error_to_json("no such file", Some(Path::new("Main.jack")))
+        );
+        assert!(error_to_json("bad", None).contains("\"path\":null,"));
+    }
+}
@@ -0,0 +1,386 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::compilation::compilation_engine::{CompilationEngine, XmlCompilationEngine};
+use crate::tokenizer::jack_tokenizer::JackTokenizer;
+use crate::tokenizer::span::Span;
+use crate::tokenizer::token::Token;
+use crate::tokenizer::token_type::TokenType;
+
+const INDENT: &str = "    ";
+
+/// Formats the jack class in `source` with canonical indentation, one statement per line and
+/// single spaces around binary operators, keeping its comments. Only whitespace changes, which is
+/// verified by comparing the parse trees of the source and the result.
+pub fn format(source: &str, path: &Path) -> Result<String> {
+    let original_xml = parse_tree(source, path)?;
+
+    let tokenizer = JackTokenizer::from_source(source, Some(path), Default::default())?;
+    let comments = tokenizer.comments().to_vec();
+    let tokens = tokenizer.tokenize_all();
+    let mut formatter = Formatter {
+        source,
+        tokens: &tokens,
+        comments: &comments,
+        next_comment: 0,
+        out: String::new(),
+        line_ending: if source.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        },
+        indent: 0,
+        nesting: 0,
+        newline: false,
+        last: Last::Nothing,
+        last_token: None,
+        last_end: 0,
+    };
+    let formatted = formatter.format();
+
+    if parse_tree(&formatted, path)? != original_xml {
+        bail!(
+            "{}: formatting would change the parse tree; the file is left as it is",
+            path.display()
+        );
+    }
+    Ok(formatted)
+}
+
+fn parse_tree(source: &str, path: &Path) -> Result<Vec<u8>> {
+    let tokenizer = JackTokenizer::from_source(source, Some(path), Default::default())?;
+    let mut xml = Vec::new();
+    XmlCompilationEngine::new(tokenizer).compile_class(&mut xml)?;
+    Ok(xml)
+}
+
+/// What was written last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Last {
+    Nothing,
+    /// The token at this index, and whether it was a unary operator.
+    Token(usize, bool),
+    Comment,
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    comments: &'a [Span],
+    next_comment: usize,
+    out: String,
+    /// The line ending of the source, which the result keeps.
+    line_ending: &'static str,
+    indent: usize,
+    /// The depth of `(` and `[`, inside which `;` does not end a line.
+    nesting: usize,
+    /// Whether the next token or comment starts a new line.
+    newline: bool,
+    last: Last,
+    /// The index of the token written last, even if a comment was written after it.
+    last_token: Option<usize>,
+    /// Where in the source the last token or comment ends.
+    last_end: usize,
+}
+
+impl Formatter<'_> {
+    fn format(&mut self) -> String {
+        for index in 0..self.tokens.len() {
+            self.write_comments_before(self.tokens[index].span().start);
+            self.write_token(index);
+        }
+        self.write_comments_before(usize::MAX);
+        self.out.push_str(self.line_ending);
+        std::mem::take(&mut self.out)
+    }
+
+    fn write_token(&mut self, index: usize) {
+        let token = &self.tokens[index];
+        let value = token.value().as_str();
+        let last_token = match self.last {
+            Last::Token(last, _) => Some(&self.tokens[last]),
+            _ => None,
+        };
+        let last_value = last_token.map(|token| token.value().as_str());
+        if value == "}" {
+            self.indent = self.indent.saturating_sub(1);
+        }
+
+        // a comment does not change whether `-` negates or subtracts
+        let previous_token = self.last_token.map(|last| &self.tokens[last]);
+        let unary = matches!(value, "-" | "~") && is_unary_position(previous_token);
+        match (value, last_value) {
+            // an empty block stays on one line
+            ("}", Some("{")) => {}
+            // `} else {`
+            ("else", Some("}")) => self.out.push(' '),
+            _ if self.newline || value == "}" => self.start_line(token.span().start),
+            _ if self.needs_space(value) => self.out.push(' '),
+            _ => {}
+        }
+        self.newline = false;
+
+        match token.token_type() {
+            TokenType::StringConst => {
+                self.out.push('"');
+                self.out.push_str(value);
+                self.out.push('"');
+            }
+            _ => self.out.push_str(value),
+        }
+        match value {
+            "{" => {
+                self.indent += 1;
+                self.newline = true;
+            }
+            "}" => self.newline = true,
+            ";" if self.nesting == 0 => self.newline = true,
+            "(" | "[" => self.nesting += 1,
+            ")" | "]" => self.nesting = self.nesting.saturating_sub(1),
+            _ => {}
+        }
+        self.last = Last::Token(index, unary);
+        self.last_token = Some(index);
+        self.last_end = token.span().end;
+    }
+
+    fn needs_space(&self, value: &str) -> bool {
+        let Last::Token(last, last_unary) = self.last else {
+            return !self.out.is_empty();
+        };
+        let last_token = &self.tokens[last];
+        if last_unary || matches!(last_token.value().as_str(), "(" | "[" | ".") {
+            return false;
+        }
+        match value {
+            ";" | "," | ")" | "]" | "." | "[" => false,
+            "(" => *last_token.token_type() != TokenType::Identifier,
+            _ => true,
+        }
+    }
+
+    /// Writes the comments that start before `limit`. A comment on the line of the previous token
+    /// stays there; any other comment gets a line of its own.
+    fn write_comments_before(&mut self, limit: usize) {
+        while let Some(span) = self.comments.get(self.next_comment) {
+            if span.start >= limit {
+                break;
+            }
+            self.next_comment += 1;
+            let text = self.source[span.start..span.end].trim_end();
+            let trailing = self.last != Last::Nothing
+                && !self.source[self.last_end..span.start].contains('\n');
+            if trailing {
+                self.out.push(' ');
+            } else {
+                self.start_line(span.start);
+            }
+            self.write_comment(span.start, text);
+            // a line comment always ends the line, and so does a comment on a line of its own
+            if text.starts_with("//") || !trailing {
+                self.newline = true;
+            }
+            self.last = Last::Comment;
+            self.last_end = span.end;
+        }
+    }
+
+    /// Writes the comment `text` that begins at `start` in the source. The continuation lines of a
+    /// block comment move as far as its first line does, and are otherwise kept as they are.
+    fn write_comment(&mut self, start: usize, text: &str) {
+        let line_start = self.source[..start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let old_column = self.source[line_start..start].chars().count();
+        let new_column = self.out[self.out.rfind('\n').map_or(0, |index| index + 1)..]
+            .chars()
+            .count();
+
+        let mut lines = text.split_inclusive('\n');
+        if let Some(first) = lines.next() {
+            self.out.push_str(first);
+        }
+        for line in lines {
+            if new_column >= old_column {
+                self.out.push_str(&" ".repeat(new_column - old_column));
+                self.out.push_str(line);
+            } else {
+                let indentation = line
+                    .chars()
+                    .take(old_column - new_column)
+                    .take_while(|c| *c == ' ' || *c == '\t')
+                    .count();
+                self.out.push_str(&line[indentation..]);
+            }
+        }
+    }
+
+    /// Starts an indented line for what begins at `start` in the source, keeping a single blank
+    /// line where the source has blank lines, except at the start and end of a block.
+    fn start_line(&mut self, start: usize) {
+        if self.out.is_empty() {
+            return;
+        }
+        self.out.push_str(self.line_ending);
+        let blank = self.source[self.last_end..start].matches('\n').count() > 1;
+        let after_open =
+            matches!(self.last, Last::Token(last, _) if self.tokens[last].value() == "{");
+        let before_close = self.source[start..].starts_with('}');
+        if blank && !after_open && !before_close {
+            self.out.push_str(self.line_ending);
+        }
+        self.out.push_str(&INDENT.repeat(self.indent));
+    }
+}
+
+/// Whether a `-` after `last` negates rather than subtracts.
+fn is_unary_position(last: Option<&Token>) -> bool {
+    match last {
+        None => true,
+        Some(last) => match last.token_type() {
+            TokenType::Symbol => !matches!(last.value().as_str(), ")" | "]"),
+            TokenType::Keyword => {
+                !matches!(last.value().as_str(), "true" | "false" | "null" | "this")
+            }
+            TokenType::Identifier | TokenType::IntConst | TokenType::StringConst => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use crate::formatter::format;
+
+    #[test]
+    fn can_format_class() {
+        let source = "\
+/** A counter. */
+class Counter{
+  field int count;static boolean   verbose;
+
+
+  constructor Counter new(){let count=0;return this;}
+
+  // adds `n` to the count
+  method void add(int n) { // n may be negative
+    let count = count+n; if(verbose){do Output.printInt(-count);}else{let count=count*(-1);}
+    while (~(count<0)) { let count=count-1; } /* not negative */
+    return;
+  }
+  method void reset(){}
+  method int at(Array a,int i){return a[i+1]-(-i);}
+}
+";
+        let expected = "\
+/** A counter. */
+class Counter {
+    field int count;
+    static boolean verbose;
+
+    constructor Counter new() {
+        let count = 0;
+        return this;
+    }
+
+    // adds `n` to the count
+    method void add(int n) { // n may be negative
+        let count = count + n;
+        if (verbose) {
+            do Output.printInt(-count);
+        } else {
+            let count = count * (-1);
+        }
+        while (~(count < 0)) {
+            let count = count - 1;
+        } /* not negative */
+        return;
+    }
+    method void reset() {}
+    method int at(Array a, int i) {
+        return a[i + 1] - (-i);
+    }
+}
+";
+
+        assert_eq!(expected, format(source, Path::new("Counter.jack")).unwrap());
+    }
+
+    #[test]
+    fn block_comments_move_with_their_first_line() {
+        let source = "\
+class Main {
+        /**
+          * Entry point.
+          *     +--+
+          *     |  |
+          */
+    function void main() {
+ /* hand
+       aligned */ return;
+    }
+}
+";
+        let expected = "\
+class Main {
+    /**
+      * Entry point.
+      *     +--+
+      *     |  |
+      */
+    function void main() {
+        /* hand
+              aligned */
+        return;
+    }
+}
+";
+
+        assert_eq!(expected, format(source, Path::new("Main.jack")).unwrap());
+    }
+
+    #[test]
+    fn operators_after_comments_keep_their_spacing() {
+        let source = "\
+class Main {
+    function int f(int a, int b) {
+        return a /* x */ - b - /* y */ -b;
+    }
+}
+";
+
+        assert_eq!(source, format(source, Path::new("Main.jack")).unwrap());
+    }
+
+    #[test]
+    fn line_endings_are_kept() {
+        let source =
+            "class Main {\r\n  /* a\r\n     b */\r\n  function void main() { return; }\r\n}\r\n";
+        let expected = "class Main {\r\n    /* a\r\n       b */\r\n    function void main() {\r\n        return;\r\n    }\r\n}\r\n";
+
+        assert_eq!(expected, format(source, Path::new("Main.jack")).unwrap());
+    }
+
+    #[test]
+    fn formatting_is_idempotent_for_every_fixture() {
+        for entry in walkdir::WalkDir::new("tests/resources") {
+            let path = entry.unwrap().into_path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "jack")
+            {
+                let source = fs::read_to_string(&path).unwrap();
+                let formatted = format(&source, &path).unwrap();
+                assert_eq!(formatted, format(&formatted, &path).unwrap(), "{:?}", path);
+            }
+        }
+    }
+
+    #[test]
+    fn syntax_errors_are_not_formatted() {
+        assert!(format("class Main { let }", Path::new("Main.jack")).is_err());
+    }
+}
@@ -1,2 +1,6 @@
+pub mod analysis;
+pub mod ast;
 pub mod compilation;
+pub mod diagnostic;
+pub mod formatter;
 pub mod tokenizer;
@@ -1,51 +1,568 @@
-use std::fs::File;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::io::{Read, Write};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{bail, Error, Result};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Parser, Subcommand, ValueEnum};
 use walkdir::{DirEntry, WalkDir};
 
-use crate::compilation::compilation_engine::CompilationEngine;
-use crate::compilation::compilation_engine::XmlCompilationEngine;
-use crate::tokenizer::jack_tokenizer::JackTokenizer;
-
-mod compilation;
-mod tokenizer;
+use jack_compiler::analysis::control_flow;
+use jack_compiler::analysis::lint;
+use jack_compiler::analysis::lint::Lint;
+use jack_compiler::analysis::parsed_class::ParsedClass;
+use jack_compiler::analysis::program::Program;
+use jack_compiler::analysis::semantic_checker;
+use jack_compiler::analysis::semantic_error::SemanticErrors;
+use jack_compiler::analysis::type_checker;
+use jack_compiler::analysis::type_checker::Strictness;
+use jack_compiler::ast::Class;
+use jack_compiler::compilation::compilation_engine::CompilationEngine;
+use jack_compiler::compilation::compilation_engine::XmlCompilationEngine;
+use jack_compiler::compilation::parse_error::{ParseError, ParseErrors};
+use jack_compiler::compilation::parser::Parser as JackParser;
+use jack_compiler::compilation::vm_compilation_engine::VmCompilationEngine;
+use jack_compiler::diagnostic::{error_to_json, Diagnostic, Severity};
+use jack_compiler::formatter;
+use jack_compiler::tokenizer::jack_tokenizer::JackTokenizer;
+use jack_compiler::tokenizer::lex_error::LexError;
+use jack_compiler::tokenizer::tokenizer_options::TokenizerOptions;
+use jack_compiler::tokenizer::xml_token_writer::XmlTokenWriter;
 
 /// Jack Compiler
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
-    /// Sets a source to be compiled. The source is a jack file or directory.
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Sets a source to be compiled. The source is a jack file or directory, or `-` for stdin.
+    #[arg(value_name = "SOURCE", required = true)]
+    path: Option<PathBuf>,
+    /// Sets the kind of output to be written next to each jack file.
+    #[arg(long, value_enum, default_value_t = Emit::Xml)]
+    emit: Emit,
+    /// Writes the output into DIR, mirroring the directory structure of the source.
+    #[arg(long, value_name = "DIR", conflicts_with = "stdout")]
+    out_dir: Option<PathBuf>,
+    /// Writes the output of a single jack file to stdout.
+    #[arg(long)]
+    stdout: bool,
+    /// Reports integer constants above 32767 as warnings instead of errors.
+    #[arg(long)]
+    allow_int_overflow: bool,
+    /// Compiles up to N files concurrently. Messages are still printed in file order.
+    #[arg(short, long, value_name = "N", default_value_t = NonZeroUsize::MIN)]
+    jobs: NonZeroUsize,
+    /// Sets how strictly the types of a program are checked before emitting vm.
+    #[arg(long, value_enum, value_name = "LEVEL", default_value_t = TypeCheck::Standard)]
+    type_check: TypeCheck,
+    /// Reports lints as warnings: all of them, or only the listed ones.
+    #[arg(
+        long,
+        value_name = "LINT",
+        num_args = 0..,
+        require_equals = true,
+        value_delimiter = ',',
+        value_parser = lint_parser()
+    )]
+    lint: Option<Vec<Lint>>,
+    /// Disables LINT, even if `--lint` lists it.
+    #[arg(long, value_name = "LINT", value_parser = lint_parser())]
+    allow: Vec<Lint>,
+    /// Prints diagnostics as reports or as one JSON object per line.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = MessageFormat::Human)]
+    message_format: MessageFormat,
+}
+
+impl Args {
+    /// SOURCE, which is only missing when a subcommand is run instead.
+    fn source(&self) -> &Path {
+        self.path.as_deref().unwrap()
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Formats jack files in place
+    Fmt(FmtArgs),
+}
+
+#[derive(clap::Args)]
+struct FmtArgs {
+    /// Sets a source to be formatted. The source is a jack file or directory, or `-` for stdin.
     #[arg(value_name = "SOURCE")]
     path: PathBuf,
+    /// Writes nothing, but fails if any file would be reformatted.
+    #[arg(long)]
+    check: bool,
+}
+
+fn lint_parser() -> impl TypedValueParser<Value = Lint> {
+    PossibleValuesParser::new(Lint::ALL.map(|lint| lint.name()))
+        .map(|name| Lint::from_name(&name).unwrap())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Emit {
+    /// Parse tree xml (Foo.xml)
+    Xml,
+    /// Parse tree xml with classified identifiers (Foo.xml)
+    XmlAnnotated,
+    /// Token xml (FooT.xml)
+    Tokens,
+    /// Hack VM code (Foo.vm), written only for files that pass the semantic checks
+    Vm,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TypeCheck {
+    /// No type checks
+    Off,
+    /// Wrong argument counts and `this` in functions only
+    Lenient,
+    /// Also booleans, objects of unrelated classes and arithmetic on objects
+    Standard,
+    /// Also mixed `int` and `char`, numbers as objects and non-boolean conditions
+    Strict,
+}
+
+impl TypeCheck {
+    fn strictness(self) -> Option<Strictness> {
+        match self {
+            TypeCheck::Off => None,
+            TypeCheck::Lenient => Some(Strictness::Lenient),
+            TypeCheck::Standard => Some(Strictness::Standard),
+            TypeCheck::Strict => Some(Strictness::Strict),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    /// Reports with the source line, and a line per file and run
+    Human,
+    /// Diagnostics only, for tools
+    Json,
+}
+
+/// What every file of a run is compiled with.
+struct Context<'a> {
+    args: &'a Args,
+    /// The source read from stdin when SOURCE is `-`.
+    stdin: Option<String>,
+    lints: Vec<Lint>,
+    /// The semantic, type and lint diagnostics of each file.
+    diagnostics: HashMap<PathBuf, Vec<Diagnostic>>,
+}
+
+impl Context<'_> {
+    fn report(&self, diagnostic: &Diagnostic) -> String {
+        match self.args.message_format {
+            MessageFormat::Human => diagnostic.report(),
+            MessageFormat::Json => diagnostic.to_json() + "\n",
+        }
+    }
 }
 
-fn main() -> Result<()> {
+fn main() {
     let args = Args::parse();
-    let files: Vec<DirEntry> = extract_files_from(args.path.as_path());
+    let result = match &args.command {
+        Some(Command::Fmt(fmt_args)) => format_files(fmt_args),
+        None => run(&args),
+    };
+    match result {
+        Ok(failed) if failed > 0 => process::exit(1),
+        Ok(_) => {}
+        Err(error) => {
+            eprint!("{}", render(&error, None, args.message_format));
+            process::exit(1);
+        }
+    }
+}
+
+/// Compiles every jack file of the source and returns the number of files that failed.
+fn run(args: &Args) -> Result<usize> {
+    let files: Vec<PathBuf> = if reads_stdin(args) {
+        if args.out_dir.is_some() {
+            bail!("a source read from stdin is written to stdout; --out-dir cannot be used");
+        }
+        vec![args.source().to_path_buf()]
+    } else {
+        extract_files_from(args.source())
+    };
 
     if files.is_empty() {
         println!(
             "The compilation target doesn't exist. Set a jack file or directory with jack files."
         );
+        return Ok(0);
+    }
+
+    if args.stdout && files.len() > 1 {
+        bail!(
+            "--stdout needs a single jack file, but {} contains {}",
+            args.source().display(),
+            files.len()
+        );
+    }
+
+    let mut context = Context {
+        args,
+        stdin: None,
+        lints: enabled_lints(args),
+        diagnostics: HashMap::new(),
+    };
+    if reads_stdin(args) {
+        let mut code = String::new();
+        io::stdin().read_to_string(&mut code)?;
+        context.stdin = Some(code);
+    }
+    if args.emit == Emit::Vm || !context.lints.is_empty() {
+        context.diagnostics = analyze(&files, &context);
+    }
+
+    let jobs = args.jobs.get().min(files.len());
+    let next_file = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let mut failed = 0;
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (files, next_file, context) = (&files, &next_file, &context);
+            scope.spawn(move || loop {
+                let index = next_file.fetch_add(1, Ordering::Relaxed);
+                let Some(file) = files.get(index) else {
+                    break;
+                };
+                let _ = sender.send((index, compile_and_log(file, context)));
+            });
+        }
+        drop(sender);
+
+        // print the logs in file order as soon as all earlier files are done
+        let mut pending = BTreeMap::new();
+        let mut next_to_print = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some((ok, log)) = pending.remove(&next_to_print) {
+                eprint!("{}", log);
+                if !ok {
+                    failed += 1;
+                }
+                next_to_print += 1;
+            }
+        }
+    });
+    if args.message_format == MessageFormat::Human {
+        eprintln!(
+            "compiled {} of {} files; {} failed",
+            files.len() - failed,
+            files.len(),
+            failed
+        );
+    }
+
+    Ok(failed)
+}
+
+/// Formats every jack file of the source and returns the number of files that failed or, with
+/// `--check`, would be reformatted.
+fn format_files(args: &FmtArgs) -> Result<usize> {
+    if args.path == Path::new("-") {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        let formatted = formatter::format(&source, Path::new("<stdin>"))?;
+        if !args.check {
+            print!("{}", formatted);
+        }
+        return Ok(usize::from(args.check && formatted != source));
+    }
+
+    let files = extract_files_from(&args.path);
+    if files.is_empty() {
+        println!(
+            "The formatting target doesn't exist. Set a jack file or directory with jack files."
+        );
+        return Ok(0);
+    }
+    let (mut changed, mut failed) = (0, 0);
+    for path in &files {
+        let result = fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|source| Ok((formatter::format(&source, path)?, source)));
+        match result {
+            Ok((formatted, source)) if formatted == source => {
+                eprintln!("{} ... unchanged", path.display())
+            }
+            Ok(_) if args.check => {
+                changed += 1;
+                eprintln!("{} ... would be reformatted", path.display());
+            }
+            Ok((formatted, _)) => {
+                changed += 1;
+                fs::write(path, formatted)?;
+                eprintln!("{} ... reformatted", path.display());
+            }
+            Err(error) => {
+                failed += 1;
+                eprint!("{}", render(&error, Some(path), MessageFormat::Human));
+                eprintln!("{} ... FAILED", path.display());
+            }
+        }
+    }
+    let verb = if args.check {
+        "would reformat"
+    } else {
+        "reformatted"
+    };
+    eprintln!(
+        "{} {} of {} files; {} failed",
+        verb,
+        changed,
+        files.len(),
+        failed
+    );
+
+    Ok(if args.check { changed + failed } else { failed })
+}
+
+/// Compiles `path` and returns whether it succeeded along with its messages.
+fn compile_and_log(path: &Path, context: &Context) -> (bool, String) {
+    let mut log = String::new();
+    let result = compile_file(path, context, &mut log);
+    let human = context.args.message_format == MessageFormat::Human;
+    match &result {
+        Ok(()) if human => log += &format!("{} ... ok\n", path.display()),
+        Ok(()) => {}
+        Err(error) => {
+            log += &render(error, Some(path), context.args.message_format);
+            if human {
+                log += &format!("{} ... FAILED\n", path.display());
+            }
+        }
+    }
+    (result.is_ok(), log)
+}
+
+/// Buffers the output and writes it only once the file compiled, so that a failed file leaves
+/// nothing behind. Warnings are appended to `log`.
+fn compile_file(path: &Path, context: &Context, log: &mut String) -> Result<()> {
+    let args = context.args;
+    let mut output = Vec::<u8>::new();
+
+    let tokenizer = tokenizer_for(path, context)?;
+    for warning in tokenizer.warnings() {
+        *log += &context.report(warning);
+    }
+    if let Some(diagnostics) = context.diagnostics.get(tokenizer.path()) {
+        let (errors, warnings): (Vec<&Diagnostic>, Vec<&Diagnostic>) = diagnostics
+            .iter()
+            .partition(|diagnostic| diagnostic.severity == Severity::Error);
+        for warning in warnings {
+            *log += &context.report(warning);
+        }
+        if !errors.is_empty() {
+            bail!(SemanticErrors {
+                diagnostics: errors.into_iter().cloned().collect(),
+            });
+        }
+    }
+    match args.emit {
+        Emit::Xml => XmlCompilationEngine::new(tokenizer).compile_class(&mut output)?,
+        Emit::XmlAnnotated => {
+            XmlCompilationEngine::annotated(tokenizer).compile_class(&mut output)?
+        }
+        Emit::Tokens => XmlTokenWriter::new(tokenizer).write_tokens(&mut output)?,
+        Emit::Vm => VmCompilationEngine::new(tokenizer).compile_class(&mut output)?,
+    }
+
+    if args.stdout || reads_stdin(args) {
+        io::stdout().write_all(&output)?;
         return Ok(());
     }
+    let output_path = output_path(path, args);
+    if let Some(dir) = output_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(output_path, output)?;
+    Ok(())
+}
+
+fn tokenizer_for(path: &Path, context: &Context) -> Result<JackTokenizer> {
+    let options = TokenizerOptions {
+        allow_int_overflow: context.args.allow_int_overflow,
+    };
+    match &context.stdin {
+        Some(code) => JackTokenizer::from_source(code.clone(), Some(Path::new("<stdin>")), options),
+        None => JackTokenizer::with_options(path, options),
+    }
+}
+
+/// The lints enabled by `--lint` and not disabled by `--allow`.
+fn enabled_lints(args: &Args) -> Vec<Lint> {
+    let lints = match &args.lint {
+        None => return Vec::new(),
+        Some(lints) if lints.is_empty() => Lint::ALL.to_vec(),
+        Some(lints) => lints.clone(),
+    };
+    lints
+        .into_iter()
+        .filter(|lint| !args.allow.contains(lint))
+        .collect()
+}
+
+/// Checks the files that parse and groups the diagnostics by file, in source order. The jack files of each directory
+/// form one program. The semantic checks run for `--emit vm` and the lints when enabled. Files
+/// that do not parse are left to `compile_file` to report.
+fn analyze(files: &[PathBuf], context: &Context) -> HashMap<PathBuf, Vec<Diagnostic>> {
+    let mut programs: BTreeMap<&Path, Vec<&PathBuf>> = BTreeMap::new();
+    for file in files {
+        let dir = file.parent().unwrap_or(Path::new(""));
+        programs.entry(dir).or_default().push(file);
+    }
+    let mut by_path: HashMap<PathBuf, Vec<Diagnostic>> = HashMap::new();
+    for program_files in programs.into_values() {
+        for diagnostic in analyze_program(&program_files, context) {
+            by_path
+                .entry(diagnostic.path.clone())
+                .or_default()
+                .push(diagnostic);
+        }
+    }
+    for diagnostics in by_path.values_mut() {
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    }
+    by_path
+}
 
+/// Checks the files of one program. A file that does not parse still declares its class, with the
+/// subroutines that could be recovered, so that the files using it are checked as usual.
+fn analyze_program(files: &[&PathBuf], context: &Context) -> Vec<Diagnostic> {
+    let mut classes = Vec::new();
+    let mut broken = Vec::new();
     for file in files {
-        let mut output_file = File::create(create_output_file_name(file.path()))?;
-        let mut engine = XmlCompilationEngine::new(JackTokenizer::new(file.path())?);
-        engine.compile_class(&mut output_file)?;
+        let parsed = tokenizer_for(file, context).and_then(ParsedClass::parse);
+        match parsed {
+            Ok(parsed) => classes.push(parsed),
+            Err(_) => broken.push((file, recover_class(file, context))),
+        }
+    }
+    let mut program = Program::new(&classes);
+    for (file, class) in &broken {
+        // a jack file is named after its class
+        let name = match class {
+            Some(class) => class.name.name.clone(),
+            None => file.file_stem().unwrap().to_string_lossy().into_owned(),
+        };
+        program.add_broken(&name, class.as_ref());
     }
 
-    Ok(())
+    let mut diagnostics = Vec::new();
+    if context.args.emit == Emit::Vm {
+        diagnostics.extend(semantic_checker::check(&program, &classes));
+        diagnostics.extend(control_flow::check(&classes));
+        if let Some(strictness) = context.args.type_check.strictness() {
+            diagnostics.extend(type_checker::check(&program, &classes, strictness));
+        }
+    }
+    diagnostics.extend(lint::check(&classes, &context.lints));
+    diagnostics
+}
+
+/// What can be parsed of the class in `file`, which has syntax errors.
+fn recover_class(file: &Path, context: &Context) -> Option<Class> {
+    let tokenizer = tokenizer_for(file, context).ok()?;
+    JackParser::new(tokenizer).parse_class_partially().ok()?.0
+}
+
+fn reads_stdin(args: &Args) -> bool {
+    args.source() == Path::new("-")
 }
 
-fn extract_files_from(path: &Path) -> Vec<DirEntry> {
+/// The output file of `path`: next to it, or at the same relative place under `--out-dir`.
+fn output_path(path: &Path, args: &Args) -> PathBuf {
+    let next_to_source = PathBuf::from(create_output_file_name(path, args.emit));
+    match &args.out_dir {
+        Some(out_dir) => {
+            let relative = path
+                .strip_prefix(args.source())
+                .ok()
+                .filter(|relative| !relative.as_os_str().is_empty())
+                .unwrap_or(Path::new(path.file_name().unwrap()));
+            out_dir
+                .join(relative)
+                .with_file_name(next_to_source.file_name().unwrap())
+        }
+        None => next_to_source,
+    }
+}
+
+/// Renders `error`, which happened while handling the file `path`, if any.
+fn render(error: &anyhow::Error, path: Option<&Path>, format: MessageFormat) -> String {
+    if format == MessageFormat::Json {
+        return match diagnostics_of(error) {
+            Some(diagnostics) => diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.to_json() + "\n")
+                .collect(),
+            None => error_to_json(&format!("{:#}", error), path) + "\n",
+        };
+    }
+    if let Some(parse_errors) = error.downcast_ref::<ParseErrors>() {
+        parse_errors.report()
+    } else if let Some(semantic_errors) = error.downcast_ref::<SemanticErrors>() {
+        semantic_errors.report()
+    } else if let Some(parse_error) = error.downcast_ref::<ParseError>() {
+        parse_error.report()
+    } else if let Some(lex_error) = error.downcast_ref::<LexError>() {
+        lex_error.report()
+    } else {
+        format!("error: {:#}\n", error)
+    }
+}
+
+/// The located errors `error` consists of, if it has a location.
+fn diagnostics_of(error: &anyhow::Error) -> Option<Vec<Diagnostic>> {
+    if let Some(parse_errors) = error.downcast_ref::<ParseErrors>() {
+        Some(
+            parse_errors
+                .errors
+                .iter()
+                .map(ParseError::to_diagnostic)
+                .collect(),
+        )
+    } else if let Some(semantic_errors) = error.downcast_ref::<SemanticErrors>() {
+        Some(semantic_errors.diagnostics.clone())
+    } else if let Some(parse_error) = error.downcast_ref::<ParseError>() {
+        Some(vec![parse_error.to_diagnostic()])
+    } else {
+        error
+            .downcast_ref::<LexError>()
+            .map(|lex_error| vec![lex_error.to_diagnostic()])
+    }
+}
+
+fn extract_files_from(path: &Path) -> Vec<PathBuf> {
     WalkDir::new(path)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(|e| e.ok())
         .filter(is_jack_file)
+        .map(|e| e.into_path())
         .collect()
 }
 
@@ -57,12 +574,20 @@ fn is_jack_file(entry: &DirEntry) -> bool {
         .unwrap_or(false)
 }
 
-fn create_output_file_name(path: &Path) -> String {
+fn create_output_file_name(path: &Path, emit: Emit) -> String {
+    let (suffix, extension) = match emit {
+        Emit::Xml | Emit::XmlAnnotated => ("", "xml"),
+        Emit::Tokens => ("T", "xml"),
+        Emit::Vm => ("", "vm"),
+    };
+
     if path.is_file() && path.extension().unwrap() == "jack" {
-        return String::from(path.with_extension("xml").to_string_lossy());
+        let stem = path.file_stem().unwrap().to_string_lossy();
+        let file_name = format!("{}{}.{}", stem, suffix, extension);
+        return String::from(path.with_file_name(file_name).to_string_lossy());
     }
 
     let dir = path.to_string_lossy();
     let file_name = path.file_name().unwrap().to_string_lossy();
-    format!("{}/{}.xml", dir, file_name)
+    format!("{}/{}{}.{}", dir, file_name, suffix, extension)
 }
@@ -1,4 +1,8 @@
 pub mod jack_tokenizer;
 pub mod key_word;
-mod token;
+pub mod lex_error;
+pub mod span;
+pub mod token;
 pub mod token_type;
+pub mod tokenizer_options;
+pub mod xml_token_writer;
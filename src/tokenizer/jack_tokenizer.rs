@@ -1,31 +1,75 @@
 use std::collections::vec_deque::VecDeque;
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use anyhow::{bail, Context, Error, Result};
 
+use crate::diagnostic::{Diagnostic, Severity};
 use crate::tokenizer::key_word::{KeyWord, KEYWORDS};
+use crate::tokenizer::lex_error::{LexError, LexErrorKind};
+use crate::tokenizer::span::{LineIndex, Span};
 use crate::tokenizer::token::Token;
 use crate::tokenizer::token_type::TokenType;
+use crate::tokenizer::tokenizer_options::TokenizerOptions;
 
 pub struct JackTokenizer {
+    path: PathBuf,
+    source: String,
+    line_index: LineIndex,
+    options: TokenizerOptions,
+    warnings: Vec<Diagnostic>,
+    comments: Vec<Span>,
     tokens: VecDeque<Token>,
     current_token: Token,
 }
 
 impl JackTokenizer {
     pub fn new(path: &Path) -> Result<Self> {
+        Self::with_options(path, Default::default())
+    }
+
+    pub fn with_options(path: &Path, options: TokenizerOptions) -> Result<Self> {
         let code = fs::read_to_string(path)?;
-        let code_without_comments = Self::remove_comments(code)?;
-        let tokens = Self::tokenize(code_without_comments)?;
+        Self::from_source(code, Some(path), options)
+    }
 
-        Ok(JackTokenizer {
-            tokens,
+    /// Tokenizes everything `reader` yields. `name` is the file name used in error messages.
+    pub fn from_reader(mut reader: impl Read, name: Option<&Path>) -> Result<Self> {
+        let mut code = String::new();
+        reader.read_to_string(&mut code)?;
+        Self::from_source(code, name, Default::default())
+    }
+
+    /// Tokenizes in-memory jack code. `name` is the file name used in error messages.
+    pub fn from_source(
+        code: impl Into<String>,
+        name: Option<&Path>,
+        options: TokenizerOptions,
+    ) -> Result<Self> {
+        let code = code.into();
+        let mut tokenizer = JackTokenizer {
+            path: name.unwrap_or(Path::new(DEFAULT_NAME)).to_path_buf(),
+            line_index: LineIndex::new(&code),
+            source: code,
+            options,
+            warnings: Vec::new(),
+            comments: Vec::new(),
+            tokens: VecDeque::new(),
             current_token: Default::default(),
-        })
+        };
+
+        tokenizer.tokens = tokenizer.tokenize()?;
+        Ok(tokenizer)
+    }
+
+    /// Consumes the tokenizer and returns every token it has not yet advanced past.
+    pub fn tokenize_all(self) -> Vec<Token> {
+        self.tokens.into()
     }
 
-    pub fn has_more_tokens(&mut self) -> Result<bool> {
+    pub fn has_more_tokens(&self) -> Result<bool> {
         Ok(!self.tokens.is_empty())
     }
 
@@ -38,15 +82,15 @@ impl JackTokenizer {
         Ok(())
     }
 
-    pub fn peek(&mut self) -> Result<&Token> {
+    pub fn peek(&self) -> Result<&Token> {
         if self.has_more_tokens()? {
-            self.tokens.get(0).context("get failed.")
+            self.tokens.front().context("get failed.")
         } else {
             bail!(Error::msg("get failed."))
         }
     }
 
-    pub fn peek_second(&mut self) -> Result<&Token> {
+    pub fn peek_second(&self) -> Result<&Token> {
         if self.tokens.len() >= 2 {
             self.tokens.get(1).context("get failed.")
         } else {
@@ -54,6 +98,38 @@ impl JackTokenizer {
         }
     }
 
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn span(&self) -> &Span {
+        self.current_token.span()
+    }
+
+    pub fn eof_span(&self) -> Span {
+        let end = self.source.trim_end().len();
+        self.line_index.span(&self.source, end, end)
+    }
+
+    /// Problems found while tokenizing that did not stop it, e.g. allowed integer overflows.
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.warnings
+    }
+
+    /// The spans of the comments in the source, in order.
+    pub fn comments(&self) -> &[Span] {
+        &self.comments
+    }
+
+    /// Returns the text of the 1-based `line` without its line break.
+    pub fn line_text(&self, line: usize) -> &str {
+        self.line_index.line_text(&self.source, line)
+    }
+
     pub fn token_type(&mut self) -> Result<&TokenType> {
         Ok(self.current_token.token_type())
     }
@@ -70,8 +146,17 @@ impl JackTokenizer {
         self.current_token.value()
     }
 
-    pub fn int_val(&self) -> Result<usize> {
-        Ok(self.current_token.value().parse::<usize>()?)
+    /// The value of the current integer constant. A constant too large for `usize`, which is only
+    /// tokenized with `allow_int_overflow`, wraps around.
+    pub fn int_val(&self) -> usize {
+        self.current_token
+            .value()
+            .bytes()
+            .fold(0usize, |value, digit| {
+                value
+                    .wrapping_mul(10)
+                    .wrapping_add(usize::from(digit - b'0'))
+            })
     }
 
     pub fn string_val(&self) -> &String {
@@ -89,140 +174,477 @@ impl JackTokenizer {
         }
     }
 
-    fn remove_comments(code: String) -> Result<String> {
-        let mut code_without_comments = String::new();
-        let mut current_index = 0;
-
-        while current_index < code.len() {
-            let current_char = &code[current_index..current_index + 1];
-
-            match current_char {
-                "/" => {
-                    let next_char = &code[current_index + 1..current_index + 2];
-                    match next_char {
-                        "/" => {
-                            let end_index = code[current_index + 1..]
-                                .find('\n')
-                                .map(|i| i + current_index + 1)
-                                .context("map failed.")?;
-                            current_index = end_index + 1;
-                        }
-                        "*" => {
-                            let end_index = code[current_index + 1..]
-                                .find("*/")
-                                .map(|i| i + current_index + 1)
-                                .context("map failed.")?;
-                            current_index = end_index + 2;
-                        }
-                        _ => {
-                            code_without_comments += &*String::from(current_char);
-                            current_index += 1;
-                        }
-                    }
-                }
-                "\r" | "\n" => current_index += 1,
-                _ => {
-                    code_without_comments += &*String::from(current_char);
-                    current_index += 1;
-                }
-            }
-        }
-        Ok(code_without_comments)
-    }
-
-    fn tokenize(source: String) -> Result<VecDeque<Token>> {
+    fn tokenize(&mut self) -> Result<VecDeque<Token>> {
         let mut tokens: VecDeque<Token> = VecDeque::new();
+        let (offsets, chars): (Vec<usize>, Vec<char>) = self.source.char_indices().unzip();
+        let (offsets, chars) = (offsets.as_slice(), chars.as_slice());
 
         let mut index = 0;
-        let chars: Vec<char> = source.chars().collect();
         while index < chars.len() {
             let current = chars[index];
-            match current {
+            let next = chars.get(index + 1);
+            let start = index;
+            let token_type = match current {
+                '/' if next == Some(&'/') => {
+                    index = Self::skip_line_comment(index, chars);
+                    self.push_comment(offsets, chars, start, index);
+                    continue;
+                }
+                '/' if next == Some(&'*') => {
+                    match Self::skip_block_comment(index, chars) {
+                        Ok(index_after_comment) => index = index_after_comment,
+                        Err(kind) => {
+                            bail!(self.lex_error(kind, offsets[start], offsets[start] + 2))
+                        }
+                    }
+                    self.push_comment(offsets, chars, start, index);
+                    continue;
+                }
                 '\"' => {
-                    index += 1;
-                    let (token, index_after_tokenize) = Self::tokenize_string_const(index, &chars)?;
-                    tokens.push_back(token);
-                    index = index_after_tokenize;
+                    match Self::skip_string_const(index + 1, chars) {
+                        Ok(index_after_string) => index = index_after_string,
+                        Err(kind) => {
+                            bail!(self.lex_error(kind, offsets[start], offsets[start] + 1))
+                        }
+                    }
+                    TokenType::StringConst
                 }
                 current if SYMBOLS.contains(&current) => {
-                    tokens.push_back(Token::new(TokenType::Symbol, String::from(current)));
                     index += 1;
+                    TokenType::Symbol
+                }
+                current if is_identifier_start(current) => {
+                    index = Self::skip_identifier(index, chars);
+                    if index < chars.len() && chars[index].is_alphabetic() {
+                        let kind = LexErrorKind::NonAsciiLetter(chars[index]);
+                        bail!(self.char_error(kind, offsets, index));
+                    }
+                    let value: String = chars[start..index].iter().collect();
+                    if KEYWORDS.contains(&value.as_str()) {
+                        TokenType::Keyword
+                    } else {
+                        TokenType::Identifier
+                    }
+                }
+                current if current.is_ascii_digit() => {
+                    index = Self::skip_int_const(index, chars);
+                    TokenType::IntConst
+                }
+                current if current.is_whitespace() => {
+                    index += 1;
+                    continue;
                 }
                 current if current.is_alphabetic() => {
-                    let (token, index_after_tokenize) =
-                        Self::tokenize_keyword_and_identifier(index, &chars)?;
-                    tokens.push_back(token);
-                    index = index_after_tokenize;
+                    let kind = LexErrorKind::NonAsciiLetter(current);
+                    bail!(self.char_error(kind, offsets, index))
                 }
-                current if current.is_numeric() => {
-                    let (token, index_after_tokenize) = Self::tokenize_int_const(index, &chars)?;
-                    tokens.push_back(token);
-                    index = index_after_tokenize;
+                current => {
+                    let kind = LexErrorKind::IllegalCharacter(current);
+                    bail!(self.char_error(kind, offsets, index))
                 }
-                _ => index += 1,
+            };
+
+            let value: String = match token_type {
+                TokenType::StringConst => chars[start + 1..index - 1].iter().collect(),
+                _ => chars[start..index].iter().collect(),
+            };
+            let end = offsets[index - 1] + chars[index - 1].len_utf8();
+            if token_type == TokenType::IntConst && !is_in_int_range(&value) {
+                let error = self.lex_error(
+                    LexErrorKind::IntegerOutOfRange(value.clone()),
+                    offsets[start],
+                    end,
+                );
+                if !self.options.allow_int_overflow {
+                    bail!(error);
+                }
+                let mut warning = error.to_diagnostic();
+                warning.severity = Severity::Warning;
+                self.warnings.push(warning);
             }
+            let span = self.line_index.span(&self.source, offsets[start], end);
+            tokens.push_back(Token::new(token_type, value, span));
         }
         Ok(tokens)
     }
 
-    fn tokenize_string_const(mut index: usize, chars: &Vec<char>) -> Result<(Token, usize)> {
-        let mut value = String::new();
-        while index < chars.len() && chars[index] != '\"' {
-            value.push(chars[index]);
+    /// Records the comment `chars[start..end]`.
+    fn push_comment(&mut self, offsets: &[usize], chars: &[char], start: usize, end: usize) {
+        let end = offsets[end - 1] + chars[end - 1].len_utf8();
+        let span = self.line_index.span(&self.source, offsets[start], end);
+        self.comments.push(span);
+    }
+
+    /// Builds an error pointing at the single character `chars[index]`.
+    fn char_error(&self, kind: LexErrorKind, offsets: &[usize], index: usize) -> LexError {
+        let start = offsets[index];
+        let end = start
+            + self.source[start..]
+                .chars()
+                .next()
+                .map_or(1, char::len_utf8);
+        self.lex_error(kind, start, end)
+    }
+
+    fn lex_error(&self, kind: LexErrorKind, start: usize, end: usize) -> LexError {
+        let span = self.line_index.span(&self.source, start, end);
+        LexError {
+            path: self.path.clone(),
+            kind,
+            span,
+            line_text: self.line_text(span.line).to_string(),
+        }
+    }
+
+    /// Skips `// ...` up to, but not including, the line break or end of file.
+    fn skip_line_comment(mut index: usize, chars: &[char]) -> usize {
+        while index < chars.len() && chars[index] != '\n' {
             index += 1;
         }
-        index += 1;
-        Ok((Token::new(TokenType::StringConst, value), index))
+        index
     }
 
-    fn tokenize_keyword_and_identifier(
-        mut index: usize,
-        chars: &Vec<char>,
-    ) -> Result<(Token, usize)> {
-        let mut value = String::new();
-        while index < chars.len() && chars[index].is_alphabetic() {
-            value.push(chars[index]);
+    /// Skips `/* ... */` and `/** ... */`.
+    fn skip_block_comment(mut index: usize, chars: &[char]) -> Result<usize, LexErrorKind> {
+        index += 2;
+        while index + 1 < chars.len() {
+            if chars[index] == '*' && chars[index + 1] == '/' {
+                return Ok(index + 2);
+            }
             index += 1;
-            if KEYWORDS.contains(&value.as_str()) {
-                return Ok((Token::new(TokenType::Keyword, value), index));
+        }
+        Err(LexErrorKind::UnterminatedComment)
+    }
+
+    /// Skips the characters of a string constant and its closing quote.
+    fn skip_string_const(mut index: usize, chars: &[char]) -> Result<usize, LexErrorKind> {
+        while index < chars.len() {
+            match chars[index] {
+                '\"' => return Ok(index + 1),
+                '\r' | '\n' => return Err(LexErrorKind::NewlineInString),
+                _ => index += 1,
             }
         }
-        Ok((Token::new(TokenType::Identifier, value), index))
+        Err(LexErrorKind::UnterminatedString)
+    }
+
+    /// identifier = (letter | '_') (letter | digit | '_')*, with ASCII letters only
+    fn skip_identifier(mut index: usize, chars: &[char]) -> usize {
+        while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') {
+            index += 1;
+        }
+        index
     }
 
-    fn tokenize_int_const(mut index: usize, chars: &Vec<char>) -> Result<(Token, usize)> {
-        let mut value = String::new();
-        while index < chars.len() && chars[index].is_numeric() {
-            value.push(chars[index]);
+    fn skip_int_const(mut index: usize, chars: &[char]) -> usize {
+        while index < chars.len() && chars[index].is_ascii_digit() {
             index += 1;
         }
-        Ok((Token::new(TokenType::IntConst, value), index))
+        index
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_in_int_range(value: &str) -> bool {
+    value.parse::<u16>().is_ok_and(|v| v <= MAX_INT_CONST)
+}
+
+/// The largest integer constant the Hack platform can represent.
+pub const MAX_INT_CONST: u16 = 32767;
+
+/// Yields the remaining tokens in order. Iterating does not change `current_token`.
+impl Iterator for JackTokenizer {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.tokens.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.tokens.len(), Some(self.tokens.len()))
+    }
+}
+
+impl ExactSizeIterator for JackTokenizer {}
+
+impl FromStr for JackTokenizer {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        Self::from_source(code, None, Default::default())
     }
 }
 
+/// The file name reported for code that does not come from a file.
+const DEFAULT_NAME: &str = "<source>";
+
 const SYMBOLS: [char; 19] = [
     '{', '}', '(', ')', '[', ']', '.', ',', ';', '+', '-', '*', '/', '&', '|', '<', '>', '=', '~',
 ];
 
 #[cfg(test)]
 mod tests {
+    use crate::diagnostic::Severity;
     use crate::tokenizer::jack_tokenizer::JackTokenizer;
+    use crate::tokenizer::key_word::KEYWORDS;
+    use crate::tokenizer::lex_error::{LexError, LexErrorKind};
+    use crate::tokenizer::span::{LineIndex, Span};
     use crate::tokenizer::token::Token;
     use crate::tokenizer::token_type::TokenType;
+    use crate::tokenizer::tokenizer_options::TokenizerOptions;
     use std::collections::VecDeque;
+    use std::io::Cursor;
+    use std::path::Path;
+    use std::str::FromStr;
 
     #[test]
     fn is_term_return_true_when_expression_with_parentheses() {
         let tokens: VecDeque<Token> = VecDeque::from([
-            Token::new(TokenType::Symbol, String::from("(")),
-            Token::new(TokenType::Identifier, String::from("x")),
+            Token::new(TokenType::Symbol, String::from("("), Default::default()),
+            Token::new(TokenType::Identifier, String::from("x"), Default::default()),
         ]);
 
         let mut tokenizer = JackTokenizer {
+            path: Default::default(),
+            source: Default::default(),
+            line_index: LineIndex::new(""),
+            options: Default::default(),
+            warnings: Vec::new(),
+            comments: Vec::new(),
             tokens,
             current_token: Default::default(),
         };
 
-        assert_eq!(true, tokenizer.is_term().unwrap())
+        assert!(tokenizer.is_term().unwrap())
+    }
+
+    #[test]
+    fn tokens_have_span_in_original_source() {
+        let src = "\
+/** doc */
+class Main { // comment
+  field String s; }
+";
+
+        let mut tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut spans = Vec::new();
+        while tokenizer.has_more_tokens().unwrap() {
+            tokenizer.advance().unwrap();
+            spans.push(*tokenizer.span());
+        }
+
+        assert_eq!(Span::new(11, 16, 2, 1), spans[0]);
+        assert_eq!(Span::new(17, 21, 2, 7), spans[1]);
+        assert_eq!(Span::new(37, 42, 3, 3), spans[3]);
+        assert_eq!(Span::new(53, 54, 3, 19), spans[7]);
+    }
+
+    #[test]
+    fn keyword_prefix_is_part_of_identifier() {
+        for keyword in KEYWORDS {
+            for identifier in [format!("{}It", keyword), format!("{}y", keyword)] {
+                let source = format!("{} {};", keyword, identifier);
+
+                assert_eq!(
+                    vec![
+                        (TokenType::Keyword, keyword.to_string()),
+                        (TokenType::Identifier, identifier),
+                        (TokenType::Symbol, ";".to_string()),
+                    ],
+                    tokenize(&source).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn identifier_may_contain_digits_and_underscores() {
+        let expected = vec![
+            (TokenType::Identifier, "x1".to_string()),
+            (TokenType::Identifier, "player_2".to_string()),
+            (TokenType::Identifier, "_tmp".to_string()),
+            (TokenType::IntConst, "2".to_string()),
+            (TokenType::Identifier, "x".to_string()),
+        ];
+
+        assert_eq!(expected, tokenize("x1 player_2 _tmp 2x").unwrap());
+    }
+
+    #[test]
+    fn non_ascii_letter_is_lex_error() {
+        let error = tokenize("let café = 1;")
+            .unwrap_err()
+            .downcast::<LexError>()
+            .unwrap();
+
+        assert_eq!(LexErrorKind::NonAsciiLetter('é'), error.kind);
+        assert_eq!(Span::new(7, 9, 1, 8), error.span);
+    }
+
+    #[test]
+    fn line_break_separates_tokens() {
+        let expected = vec![
+            (TokenType::Keyword, "var".to_string()),
+            (TokenType::Keyword, "int".to_string()),
+            (TokenType::Identifier, "x".to_string()),
+            (TokenType::Symbol, ";".to_string()),
+        ];
+
+        assert_eq!(expected, tokenize("var int\nx;").unwrap());
+        assert_eq!(expected, tokenize("var int\r\nx;").unwrap());
+    }
+
+    #[test]
+    fn line_comment_may_end_at_end_of_file() {
+        let expected = vec![(TokenType::Symbol, "}".to_string())];
+
+        assert_eq!(expected, tokenize("} // end").unwrap());
+    }
+
+    #[test]
+    fn comments_are_recorded_in_order() {
+        let source = "/** doc */ class // end\n";
+        let tokenizer = JackTokenizer::from_source(source, None, Default::default()).unwrap();
+
+        let comments: Vec<&str> = tokenizer
+            .comments()
+            .iter()
+            .map(|span| &source[span.start..span.end])
+            .collect();
+        assert_eq!(vec!["/** doc */", "// end"], comments);
+        assert_eq!(Span::new(17, 23, 1, 18), tokenizer.comments()[1]);
+    }
+
+    #[test]
+    fn comment_markers_in_string_constant_are_kept() {
+        let expected = vec![
+            (TokenType::StringConst, "http://a/*b*/".to_string()),
+            (TokenType::Symbol, ";".to_string()),
+        ];
+
+        assert_eq!(expected, tokenize("\"http://a/*b*/\"; /* c */").unwrap());
+    }
+
+    #[test]
+    fn unterminated_string_constant_is_lex_error() {
+        let error = tokenize("do f(\"abc);")
+            .unwrap_err()
+            .downcast::<LexError>()
+            .unwrap();
+
+        assert_eq!(LexErrorKind::UnterminatedString, error.kind);
+        assert_eq!(Span::new(5, 6, 1, 6), error.span);
+    }
+
+    #[test]
+    fn line_break_in_string_constant_is_lex_error() {
+        let error = tokenize("let s = \"ab\ncd\";")
+            .unwrap_err()
+            .downcast::<LexError>()
+            .unwrap();
+
+        assert_eq!(LexErrorKind::NewlineInString, error.kind);
+        assert_eq!(Span::new(8, 9, 1, 9), error.span);
+    }
+
+    #[test]
+    fn unterminated_block_comment_is_lex_error() {
+        let error = tokenize("class Main {\n/** doc\n}")
+            .unwrap_err()
+            .downcast::<LexError>()
+            .unwrap();
+
+        assert_eq!(LexErrorKind::UnterminatedComment, error.kind);
+        assert_eq!(Span::new(13, 15, 2, 1), error.span);
+    }
+
+    #[test]
+    fn integer_constant_above_32767_is_lex_error() {
+        assert_eq!(
+            vec![(TokenType::IntConst, "32767".to_string())],
+            tokenize("32767").unwrap()
+        );
+
+        let error = tokenize("let x = 32768;")
+            .unwrap_err()
+            .downcast::<LexError>()
+            .unwrap();
+
+        assert_eq!(
+            LexErrorKind::IntegerOutOfRange("32768".to_string()),
+            error.kind
+        );
+        assert_eq!(Span::new(8, 13, 1, 9), error.span);
+    }
+
+    #[test]
+    fn integer_constant_out_of_range_is_warning_when_allowed() {
+        let src = "\
+let x = 99999999999999999999;
+";
+        let options = TokenizerOptions {
+            allow_int_overflow: true,
+        };
+
+        let tokenizer = JackTokenizer::from_source(src, None, options).unwrap();
+
+        assert_eq!(1, tokenizer.warnings().len());
+        assert_eq!(Severity::Warning, tokenizer.warnings()[0].severity);
+        assert_eq!(Span::new(8, 28, 1, 9), tokenizer.warnings()[0].span);
+    }
+
+    #[test]
+    fn integer_constant_too_large_for_usize_wraps_around() {
+        let options = TokenizerOptions {
+            allow_int_overflow: true,
+        };
+        let mut tokenizer =
+            JackTokenizer::from_source("40000 99999999999999999999", None, options).unwrap();
+
+        tokenizer.advance().unwrap();
+        assert_eq!(40000, tokenizer.int_val());
+        tokenizer.advance().unwrap();
+        assert_eq!(
+            (99999999999999999999u128 % (1u128 << usize::BITS)) as usize,
+            tokenizer.int_val()
+        );
+    }
+
+    #[test]
+    fn can_tokenize_from_reader_with_name() {
+        let reader = Cursor::new("let x = 1;\nlet y = 2;\n");
+
+        let mut tokenizer =
+            JackTokenizer::from_reader(reader, Some(Path::new("Main.jack"))).unwrap();
+        let mut count = 0;
+        while tokenizer.has_more_tokens().unwrap() {
+            tokenizer.advance().unwrap();
+            count += 1;
+        }
+
+        assert_eq!(10, count);
+        assert_eq!(Path::new("Main.jack"), tokenizer.path());
+        assert_eq!("let y = 2;", tokenizer.line_text(tokenizer.span().line));
+    }
+
+    #[test]
+    fn iterator_yields_remaining_tokens() {
+        let mut tokenizer = JackTokenizer::from_str("do f();").unwrap();
+        tokenizer.advance().unwrap();
+
+        let values: Vec<String> = tokenizer.map(|token| token.value().clone()).collect();
+
+        assert_eq!(vec!["f", "(", ")", ";"], values);
+    }
+
+    fn tokenize(source: &str) -> anyhow::Result<Vec<(TokenType, String)>> {
+        let tokens = JackTokenizer::from_str(source)?.tokenize_all();
+        Ok(tokens
+            .into_iter()
+            .map(|token| (*token.token_type(), token.value().clone()))
+            .collect())
     }
 }
@@ -3,7 +3,7 @@ use std::fmt::Formatter;
 
 use anyhow::{bail, Error, Result};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KeyWord {
     Class,
     Method,
@@ -0,0 +1,101 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::tokenizer::jack_tokenizer::MAX_INT_CONST;
+use crate::tokenizer::span::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexErrorKind {
+    /// A letter outside ASCII, which jack identifiers may not contain.
+    NonAsciiLetter(char),
+    IllegalCharacter(char),
+    /// A string constant without its closing quote before end of file.
+    UnterminatedString,
+    /// A line break inside a string constant, which jack does not allow.
+    NewlineInString,
+    /// A `/*` comment without its closing `*/`.
+    UnterminatedComment,
+    /// An integer constant above 32767.
+    IntegerOutOfRange(String),
+}
+
+impl fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LexErrorKind::NonAsciiLetter(c) => write!(
+                f,
+                "non-ASCII letter '{}' in identifier; identifiers may only contain ASCII letters, digits and '_'",
+                c
+            ),
+            LexErrorKind::IllegalCharacter(c) => write!(f, "illegal character '{}'", c),
+            LexErrorKind::UnterminatedString => write!(f, "unterminated string constant"),
+            LexErrorKind::NewlineInString => {
+                write!(f, "string constant may not contain a line break")
+            }
+            LexErrorKind::UnterminatedComment => write!(f, "unterminated block comment"),
+            LexErrorKind::IntegerOutOfRange(value) => write!(
+                f,
+                "integer constant {} is out of range; jack integers must be between 0 and {}",
+                value, MAX_INT_CONST
+            ),
+        }
+    }
+}
+
+impl LexErrorKind {
+    fn note(&self) -> Option<&'static str> {
+        match self {
+            LexErrorKind::UnterminatedString | LexErrorKind::NewlineInString => {
+                Some("the string constant starts here")
+            }
+            LexErrorKind::UnterminatedComment => Some("the comment starts here"),
+            _ => None,
+        }
+    }
+}
+
+/// An error found while splitting the source into tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub path: PathBuf,
+    pub kind: LexErrorKind,
+    pub span: Span,
+    /// The source line containing `span`, used to render the report.
+    pub line_text: String,
+}
+
+impl LexError {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            code: None,
+            message: self.kind.to_string(),
+            path: self.path.clone(),
+            span: self.span,
+            line_text: self.line_text.clone(),
+            note: self.kind.note().map(str::to_string),
+        }
+    }
+
+    /// Renders the error with the offending source line and a caret underline.
+    pub fn report(&self) -> String {
+        self.to_diagnostic().report()
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.span.line,
+            self.span.column,
+            self.kind
+        )
+    }
+}
+
+impl std::error::Error for LexError {}
@@ -0,0 +1,74 @@
+/// Location of a token in the original source.
+///
+/// `start` and `end` are byte offsets (`end` is exclusive), `line` and `column`
+/// are 1-based and point at the first character of the token.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Returns the span from the start of `self` to the end of `other`.
+    pub fn to(&self, other: Span) -> Span {
+        Span::new(self.start, other.end, self.line, self.column)
+    }
+}
+
+/// Byte offsets of the start of every line, used to turn offsets into spans.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { line_starts }
+    }
+
+    pub fn span(&self, source: &str, start: usize, end: usize) -> Span {
+        let line = self.line_starts.partition_point(|&s| s <= start);
+        let line_start = self.line_starts[line - 1];
+        let column = source[line_start..start].chars().count() + 1;
+        Span::new(start, end, line, column)
+    }
+
+    /// Returns the text of the 1-based `line` without its line break.
+    pub fn line_text<'a>(&self, source: &'a str, line: usize) -> &'a str {
+        let start = self.line_starts[line - 1];
+        let end = self
+            .line_starts
+            .get(line)
+            .map(|next| next - 1)
+            .unwrap_or(source.len());
+        source[start..end].trim_end_matches('\r')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::span::{LineIndex, Span};
+
+    #[test]
+    fn span_has_line_and_column() {
+        let source = "class Main {\n  field int x;\n}\n";
+        let start = source.find('x').unwrap();
+
+        let actual = LineIndex::new(source).span(source, start, start + 1);
+
+        assert_eq!(Span::new(start, start + 1, 2, 13), actual);
+    }
+}
@@ -1,12 +1,14 @@
 use crate::tokenizer::key_word::KeyWord;
+use crate::tokenizer::span::Span;
 use crate::tokenizer::token_type::TokenType;
 use anyhow::Result;
 use std::fmt::Debug;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Token {
     token_type: TokenType,
     value: String,
+    span: Span,
 }
 
 impl Default for Token {
@@ -14,13 +16,18 @@ impl Default for Token {
         Self {
             token_type: TokenType::Keyword,
             value: "".to_string(),
+            span: Default::default(),
         }
     }
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, value: String) -> Self {
-        Token { token_type, value }
+    pub fn new(token_type: TokenType, value: String, span: Span) -> Self {
+        Token {
+            token_type,
+            value,
+            span,
+        }
     }
 
     pub fn token_type(&self) -> &TokenType {
@@ -31,6 +38,10 @@ impl Token {
         &self.value
     }
 
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
     pub fn is_type(&self) -> Result<bool> {
         match self.token_type {
             TokenType::Keyword => match KeyWord::from(&self.value)? {
@@ -0,0 +1,6 @@
+/// Settings that change how strictly `JackTokenizer` treats its input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TokenizerOptions {
+    /// Reports integer constants above 32767 as warnings instead of errors.
+    pub allow_int_overflow: bool,
+}
@@ -0,0 +1,100 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::tokenizer::jack_tokenizer::JackTokenizer;
+use crate::tokenizer::token_type::TokenType;
+
+/// Writes the flat `<tokens>` xml (the `*T.xml` format of nand2tetris project 10).
+pub struct XmlTokenWriter {
+    tokenizer: JackTokenizer,
+}
+
+impl XmlTokenWriter {
+    pub fn new(tokenizer: JackTokenizer) -> Self {
+        XmlTokenWriter { tokenizer }
+    }
+
+    pub fn write_tokens(&mut self, writer: &mut impl Write) -> Result<()> {
+        writeln!(writer, "<tokens>")?;
+        while self.tokenizer.has_more_tokens()? {
+            self.tokenizer.advance()?;
+            match self.tokenizer.token_type()? {
+                TokenType::Keyword => writeln!(
+                    writer,
+                    "<keyword> {} </keyword>",
+                    self.tokenizer.key_word()?.to_string().to_lowercase()
+                )?,
+                TokenType::Symbol => {
+                    let symbol = match self.tokenizer.symbol() {
+                        '<' => "&lt;",
+                        '>' => "&gt;",
+                        '&' => "&amp;",
+                        _ => "",
+                    };
+
+                    if symbol.is_empty() {
+                        writeln!(writer, "<symbol> {} </symbol>", self.tokenizer.symbol())?
+                    } else {
+                        writeln!(writer, "<symbol> {} </symbol>", symbol)?
+                    }
+                }
+                TokenType::Identifier => writeln!(
+                    writer,
+                    "<identifier> {} </identifier>",
+                    self.tokenizer.identifier()
+                )?,
+                TokenType::IntConst => writeln!(
+                    writer,
+                    "<integerConstant> {} </integerConstant>",
+                    self.tokenizer.int_val()
+                )?,
+                TokenType::StringConst => writeln!(
+                    writer,
+                    "<stringConstant> {} </stringConstant>",
+                    self.tokenizer.string_val()
+                )?,
+            }
+        }
+        writeln!(writer, "</tokens>")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::tokenizer::jack_tokenizer::JackTokenizer;
+    use crate::tokenizer::xml_token_writer::XmlTokenWriter;
+
+    #[test]
+    fn can_write_tokens() {
+        let expected = "\
+<tokens>
+<keyword> let </keyword>
+<identifier> x </identifier>
+<symbol> = </symbol>
+<identifier> x </identifier>
+<symbol> &lt; </symbol>
+<integerConstant> 10 </integerConstant>
+<symbol> ; </symbol>
+</tokens>
+"
+        .to_string();
+
+        let src = "\
+let x = x < 10;
+";
+        let mut output = Vec::<u8>::new();
+
+        let tokenizer = JackTokenizer::from_str(src).unwrap();
+        let mut writer = XmlTokenWriter::new(tokenizer);
+
+        let result = writer.write_tokens(&mut output);
+        let actual = String::from_utf8(output).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,556 @@
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use tempfile::TempDir;
+
+const BROKEN: &str = "\
+class Broken {
+    function void main() {
+        let x = ;
+        return;
+    }
+}
+";
+
+fn jack_compiler(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_jack-compiler"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn source_dir(files: &[(&str, &str)]) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, source) in files {
+        fs::write(dir.path().join(name), source).unwrap();
+    }
+    dir
+}
+
+fn seven() -> String {
+    fs::read_to_string("tests/resources/Seven/Main.jack").unwrap()
+}
+
+#[test]
+fn failing_file_does_not_stop_the_directory() {
+    let seven = seven();
+    let dir = source_dir(&[("Broken.jack", BROKEN), ("Seven.jack", &seven)]);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap()]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("Broken.jack ... FAILED"));
+    assert!(stderr.contains("Seven.jack ... ok"));
+    assert!(stderr.contains("compiled 1 of 2 files; 1 failed"));
+    assert!(dir.path().join("Seven.xml").exists());
+    assert!(!dir.path().join("Broken.xml").exists());
+}
+
+#[test]
+fn successful_directory_exits_with_zero() {
+    let seven = seven();
+    let dir = source_dir(&[("Main.jack", &seven)]);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap(), "--emit", "vm"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.status.success());
+    assert!(stderr.contains("compiled 1 of 1 files; 0 failed"));
+    let expected = fs::read_to_string("tests/resources/Seven/Main.vm").unwrap();
+    let actual = fs::read_to_string(dir.path().join("Main.vm")).unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn out_dir_mirrors_the_source_tree() {
+    let seven = seven();
+    let dir = source_dir(&[("Seven.jack", &seven)]);
+    fs::create_dir(dir.path().join("nested")).unwrap();
+    fs::write(dir.path().join("nested/Main.jack"), &seven).unwrap();
+    let out_dir = tempfile::tempdir().unwrap();
+
+    let output = jack_compiler(&[
+        dir.path().to_str().unwrap(),
+        "--out-dir",
+        out_dir.path().to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert!(out_dir.path().join("Seven.xml").exists());
+    assert!(out_dir.path().join("nested/Main.xml").exists());
+    assert!(!dir.path().join("Seven.xml").exists());
+    assert!(!dir.path().join("nested/Main.xml").exists());
+}
+
+#[test]
+fn each_directory_is_compiled_as_its_own_program() {
+    let out_dir = tempfile::tempdir().unwrap();
+
+    let output = jack_compiler(&[
+        "tests/resources",
+        "--emit",
+        "vm",
+        "--out-dir",
+        out_dir.path().to_str().unwrap(),
+    ]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!stderr.contains("duplicate declaration"));
+    for file in [
+        "ArrayTest/Main.jack",
+        "Seven/Main.jack",
+        "Square/Main.jack",
+        "Square/Square.jack",
+        "Square/SquareGame.jack",
+    ] {
+        assert!(stderr.contains(&format!("tests/resources/{} ... ok", file)));
+    }
+    // the parse-only version of Square returns values that are not `this` from constructors
+    assert!(stderr.contains("compiled 6 of 8 files; 2 failed"));
+    let expected = fs::read_to_string("tests/resources/Seven/Main.vm").unwrap();
+    let actual = fs::read_to_string(out_dir.path().join("Seven/Main.vm")).unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn stdout_writes_the_output_of_a_single_file() {
+    let output = jack_compiler(&[
+        "tests/resources/Seven/Main.jack",
+        "--stdout",
+        "--emit",
+        "vm",
+    ]);
+
+    let expected = fs::read_to_string("tests/resources/Seven/Main.vm").unwrap();
+    assert!(output.status.success());
+    assert_eq!(expected, String::from_utf8(output.stdout).unwrap());
+}
+
+#[test]
+fn stdout_rejects_a_directory_with_several_files() {
+    let output = jack_compiler(&["tests/resources/Square", "--stdout"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("--stdout needs a single jack file"));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn dash_reads_the_source_from_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jack-compiler"))
+        .args(["-", "--emit", "vm"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(seven().as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let expected = fs::read_to_string("tests/resources/Seven/Main.vm").unwrap();
+    assert!(output.status.success());
+    assert_eq!(expected, String::from_utf8(output.stdout).unwrap());
+}
+
+#[test]
+fn allowed_int_overflow_only_warns() {
+    let main = "\
+class Main {
+    function void main() {
+        do Output.printInt(99999999999999999999);
+        return;
+    }
+}
+";
+    let dir = source_dir(&[("Main.jack", main)]);
+    let path = dir.path().to_str().unwrap();
+
+    let output = jack_compiler(&[path]);
+    assert!(!output.status.success());
+
+    let output = jack_compiler(&[path, "--allow-int-overflow"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("warning: integer constant 99999999999999999999 is out of range"));
+    assert!(stderr.contains("Main.jack:3:28"));
+    assert!(stderr.contains("Main.jack ... ok"));
+    assert!(dir.path().join("Main.xml").exists());
+}
+
+#[test]
+fn jobs_keep_messages_in_file_order() {
+    let seven = seven();
+    let names = ["A", "B", "C", "D", "E", "F", "G", "H"];
+    let sources: Vec<(String, &str)> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let source = if i % 3 == 1 { BROKEN } else { seven.as_str() };
+            (format!("{}.jack", name), source)
+        })
+        .collect();
+    let files: Vec<(&str, &str)> = sources
+        .iter()
+        .map(|(name, source)| (name.as_str(), *source))
+        .collect();
+    let dir = source_dir(&files);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap(), "--jobs", "4"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    let statuses: Vec<&str> = stderr
+        .lines()
+        .filter(|line| line.ends_with(" ... ok") || line.ends_with(" ... FAILED"))
+        .collect();
+    let expected: Vec<String> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let path = dir.path().join(format!("{}.jack", name));
+            let status = if i % 3 == 1 { "FAILED" } else { "ok" };
+            format!("{} ... {}", path.display(), status)
+        })
+        .collect();
+    assert!(!output.status.success());
+    assert_eq!(expected, statuses);
+    assert!(stderr.ends_with("compiled 5 of 8 files; 3 failed\n"));
+    // each error report is printed right before the status of its file
+    let report = stderr.find("expected term").unwrap();
+    let b_status = stderr.find("B.jack ... FAILED").unwrap();
+    let a_status = stderr.find("A.jack ... ok").unwrap();
+    assert!(a_status < report && report < b_status);
+}
+
+#[test]
+fn tokens_after_the_class_fail_the_file() {
+    let dir = source_dir(&[(
+        "Main.jack",
+        "class Main { function void f() { return; } } }\n",
+    )]);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap(), "--emit", "vm"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("error: expected end of file, found symbol '}'"));
+    assert!(stderr.contains("Main.jack ... FAILED"));
+    assert!(!dir.path().join("Main.vm").exists());
+}
+
+#[test]
+fn vm_is_not_written_for_semantic_errors() {
+    let main = "\
+class Main {
+    function void main() {
+        do Helper.run();
+        do Helper.missing();
+        return;
+    }
+}
+";
+    let helper = "\
+class Helper {
+    function void run() {
+        let count = 1;
+        return;
+    }
+}
+";
+    let dir = source_dir(&[("Main.jack", main), ("Helper.jack", helper)]);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap(), "--emit", "vm"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("error: undeclared variable `count`"));
+    assert!(stderr.contains("error: subroutine `missing` is not declared in class `Helper`"));
+    assert!(stderr.contains("compiled 0 of 2 files; 2 failed"));
+    assert!(!dir.path().join("Main.vm").exists());
+    assert!(!dir.path().join("Helper.vm").exists());
+}
+
+#[test]
+fn classes_of_broken_files_are_still_declared() {
+    let main = "\
+class Main {
+    function void main() {
+        var Point p;
+        let p = Point.new();
+        do p.draw();
+        do Line.draw(p, p);
+        return;
+    }
+}
+";
+    let point = "\
+class Point {
+    constructor Point new() { return this; }
+    method void draw( { return; }
+}
+";
+    let line = "class Line extends Shape { function void draw(Point a, Point b) { return; } }\n";
+    let dir = source_dir(&[
+        ("Main.jack", main),
+        ("Point.jack", point),
+        ("Line.jack", line),
+    ]);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap(), "--emit", "vm"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stderr.contains("Main.jack ... ok"));
+    assert!(stderr.contains("Point.jack ... FAILED"));
+    assert!(stderr.contains("Line.jack ... FAILED"));
+    assert!(!stderr.contains("undeclared"));
+    assert!(!stderr.contains("is not declared"));
+    assert!(stderr.contains("compiled 1 of 3 files; 2 failed"));
+}
+
+#[test]
+fn diagnostics_of_a_file_are_in_source_order() {
+    let main = "\
+class Main {
+    function int main() {
+        do Output.printInt(Math.max(1, 2, 3) + missing);
+    }
+}
+";
+    let dir = source_dir(&[("Main.jack", main)]);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap(), "--emit", "vm"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    let no_return = stderr.find("can end without `return`").unwrap();
+    let arguments = stderr.find("takes 2 arguments but 3 were given").unwrap();
+    let undeclared = stderr.find("undeclared variable `missing`").unwrap();
+    assert!(
+        no_return < arguments && arguments < undeclared,
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn type_check_level_decides_what_is_reported() {
+    let main = "\
+class Main {
+    function void main() {
+        var boolean done;
+        var char c;
+        let done = 1;
+        let c = 65;
+        do Output.printInt(c);
+        return;
+    }
+}
+";
+    let dir = source_dir(&[("Main.jack", main)]);
+    let path = dir.path().to_str().unwrap();
+
+    let output = jack_compiler(&[path, "--emit", "vm"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success());
+    assert!(stderr.contains("warning: cannot assign `int` to `done` of type `boolean`"));
+    assert!(!stderr.contains("to `c` of type `char`"));
+    assert!(dir.path().join("Main.vm").exists());
+
+    let output = jack_compiler(&[path, "--emit", "vm", "--type-check", "strict"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("warning: cannot assign `int` to `c` of type `char`"));
+    assert!(stderr.contains("argument 1 of `Output.printInt` has type `char`, expected `int`"));
+
+    let output = jack_compiler(&[path, "--emit", "vm", "--type-check", "off"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("warning"));
+}
+
+#[test]
+fn wrong_argument_count_fails_the_file() {
+    let main = "\
+class Main {
+    function void main() {
+        do Output.printInt(1, 2);
+        return;
+    }
+}
+";
+    let dir = source_dir(&[("Main.jack", main)]);
+
+    let output = jack_compiler(&[dir.path().to_str().unwrap(), "--emit", "vm"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("error: `Output.printInt` takes 1 argument but 2 were given"));
+    assert!(!dir.path().join("Main.vm").exists());
+}
+
+const UNUSED: &str = "\
+class Main {
+    field int size;
+    function void main() {
+        var int size, count;
+        let size = 1;
+        return;
+    }
+}
+";
+
+#[test]
+fn lint_reports_enabled_lints_as_warnings() {
+    let dir = source_dir(&[("Main.jack", UNUSED)]);
+    let path = dir.path().to_str().unwrap();
+
+    let output = jack_compiler(&[path]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("warning"));
+
+    let output = jack_compiler(&[path, "--lint"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success());
+    assert!(stderr.contains("warning[unused-field]: unused field `size`"));
+    assert!(stderr.contains("warning[shadowing]: variable `size` shadows field `size`"));
+    assert!(stderr.contains("warning[unused-variable]: unused variable `count`"));
+
+    let output = jack_compiler(&[
+        path,
+        "--lint=unused-variable,shadowing",
+        "--allow",
+        "shadowing",
+    ]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(2, stderr.matches("warning[unused-variable]").count());
+    assert!(!stderr.contains("shadowing"));
+    assert!(!stderr.contains("unused-field"));
+}
+
+#[test]
+fn json_message_format_prints_one_object_per_diagnostic() {
+    let dir = source_dir(&[("Main.jack", UNUSED), ("Broken.jack", BROKEN)]);
+    let path = dir.path().to_str().unwrap();
+
+    let output = jack_compiler(&[path, "--lint=unused-field", "--message-format", "json"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines: Vec<&str> = stderr.lines().collect();
+
+    assert!(!output.status.success());
+    assert_eq!(2, lines.len());
+    assert!(lines[0].starts_with("{\"severity\":\"error\",\"code\":null,\"message\":\"expected"));
+    assert!(lines[1].starts_with(
+        "{\"severity\":\"warning\",\"code\":\"unused-field\",\"message\":\"unused field `size`\""
+    ));
+    assert!(lines[1].ends_with("\"line\":2,\"column\":15,\"start\":27,\"end\":31,\"note\":null}"));
+}
+
+#[test]
+fn json_message_format_prints_errors_without_location_as_json() {
+    let dir = source_dir(&[("Main.jack", UNUSED)]);
+    fs::write(dir.path().join("Latin1.jack"), b"class Latin1 { \xe9 }").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = jack_compiler(&[path, "--message-format", "json"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines: Vec<&str> = stderr.lines().collect();
+
+    assert!(!output.status.success());
+    assert_eq!(1, lines.len(), "{}", stderr);
+    assert!(lines[0].starts_with("{\"severity\":\"error\",\"code\":null,\"message\":"));
+    assert!(lines[0].contains("Latin1.jack\",\"line\":null,\"column\":null,"));
+
+    let output = jack_compiler(&[path, "--stdout", "--message-format", "json"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with(
+        "{\"severity\":\"error\",\"code\":null,\"message\":\"--stdout needs a single jack file"
+    ));
+    assert!(stderr.ends_with(
+        "\"path\":null,\"line\":null,\"column\":null,\"start\":null,\"end\":null,\"note\":null}\n"
+    ));
+}
+
+const UNFORMATTED: &str = "\
+class Main {
+  function void main() { do Output.printInt(1+2); // three
+  return; }
+}
+";
+
+const FORMATTED: &str = "\
+class Main {
+    function void main() {
+        do Output.printInt(1 + 2); // three
+        return;
+    }
+}
+";
+
+#[test]
+fn fmt_check_fails_without_writing_when_a_file_would_change() {
+    let dir = source_dir(&[("Main.jack", UNFORMATTED), ("Done.jack", FORMATTED)]);
+    let path = dir.path().to_str().unwrap();
+
+    let output = jack_compiler(&["fmt", "--check", path]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("Done.jack ... unchanged"));
+    assert!(stderr.contains("Main.jack ... would be reformatted"));
+    assert!(stderr.contains("would reformat 1 of 2 files; 0 failed"));
+    assert_eq!(
+        UNFORMATTED,
+        fs::read_to_string(dir.path().join("Main.jack")).unwrap()
+    );
+}
+
+#[test]
+fn fmt_rewrites_files_in_place() {
+    let dir = source_dir(&[("Main.jack", UNFORMATTED), ("Broken.jack", BROKEN)]);
+    let path = dir.path().to_str().unwrap();
+
+    let output = jack_compiler(&["fmt", path]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("Broken.jack ... FAILED"));
+    assert_eq!(
+        BROKEN,
+        fs::read_to_string(dir.path().join("Broken.jack")).unwrap()
+    );
+    assert_eq!(
+        FORMATTED,
+        fs::read_to_string(dir.path().join("Main.jack")).unwrap()
+    );
+    assert!(jack_compiler(&[
+        "fmt",
+        "--check",
+        dir.path().join("Main.jack").to_str().unwrap()
+    ])
+    .status
+    .success());
+}
+
+#[test]
+fn fmt_formats_stdin_to_stdout() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jack-compiler"))
+        .args(["fmt", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(UNFORMATTED.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(FORMATTED, String::from_utf8(output.stdout).unwrap());
+}
@@ -4,6 +4,7 @@ use std::path::Path;
 use std::string::String;
 
 use jack_compiler::tokenizer::jack_tokenizer::JackTokenizer;
+use jack_compiler::tokenizer::token::Token;
 use jack_compiler::tokenizer::token_type::TokenType;
 
 #[test]
@@ -34,6 +35,22 @@ fn array_test_main() {
     test_diff(expected_file_path, src_path);
 }
 
+#[test]
+fn iterator_yields_same_tokens_as_advance() {
+    let src_path = Path::new("tests/resources/Square/SquareGame.jack");
+    let mut jack_tokenizer = JackTokenizer::new(src_path).unwrap();
+    let mut expected: Vec<String> = Vec::new();
+    while jack_tokenizer.has_more_tokens().unwrap() {
+        jack_tokenizer.advance().unwrap();
+        expected.push(jack_tokenizer.identifier().clone());
+    }
+
+    let tokens: Vec<Token> = JackTokenizer::new(src_path).unwrap().collect();
+    let actual: Vec<String> = tokens.iter().map(|token| token.value().clone()).collect();
+
+    assert_eq!(expected, actual)
+}
+
 fn test_diff(expected_file_path: &Path, src_file_path: &Path) {
     let expected = read_to_string(expected_file_path).unwrap();
     let mut jack_tokenizer = JackTokenizer::new(src_file_path).unwrap();
@@ -76,7 +93,7 @@ fn test_diff(expected_file_path: &Path, src_file_path: &Path) {
             TokenType::IntConst => writeln!(
                 actual,
                 "<integerConstant> {} </integerConstant>",
-                jack_tokenizer.int_val().unwrap()
+                jack_tokenizer.int_val()
             )
             .unwrap(),
             TokenType::StringConst => writeln!(
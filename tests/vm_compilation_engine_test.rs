@@ -0,0 +1,61 @@
+use std::fs::read_to_string;
+use std::path::Path;
+use std::string::String;
+
+use jack_compiler::compilation::compilation_engine::CompilationEngine;
+use jack_compiler::compilation::vm_compilation_engine::VmCompilationEngine;
+use jack_compiler::tokenizer::jack_tokenizer::JackTokenizer;
+
+#[test]
+fn seven_main() {
+    let expected_file_path = Path::new("tests/resources/Seven/Main.vm");
+    let src_path = Path::new("tests/resources/Seven/Main.jack");
+    test_diff(expected_file_path, src_path);
+}
+
+#[test]
+fn array_test_main() {
+    let expected_file_path = Path::new("tests/resources/ArrayTest/Main.vm");
+    let src_path = Path::new("tests/resources/ArrayTest/Main.jack");
+    test_diff(expected_file_path, src_path);
+}
+
+#[test]
+fn square() {
+    for class in ["Main", "Square", "SquareGame"] {
+        let dir = Path::new("tests/resources/Square");
+        test_diff(
+            &dir.join(format!("{}.vm", class)),
+            &dir.join(format!("{}.jack", class)),
+        );
+    }
+}
+
+#[test]
+fn compiles_every_fixture_class() {
+    for dir in ["ArrayTest", "ExpressionLessSquare", "Square"] {
+        for entry in Path::new("tests/resources").join(dir).read_dir().unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "jack")
+            {
+                let jack_tokenizer = JackTokenizer::new(&path).unwrap();
+                let mut compilation_engine = VmCompilationEngine::new(jack_tokenizer);
+                let result = compilation_engine.compile_class(&mut Vec::<u8>::new());
+                assert!(result.is_ok(), "{}: {:?}", path.display(), result);
+            }
+        }
+    }
+}
+
+fn test_diff(expected_file_path: &Path, src_file_path: &Path) {
+    let expected = read_to_string(expected_file_path).unwrap();
+    let jack_tokenizer = JackTokenizer::new(src_file_path).unwrap();
+    let mut compilation_engine = VmCompilationEngine::new(jack_tokenizer);
+    let mut output = Vec::<u8>::new();
+    let result = compilation_engine.compile_class(&mut output);
+    let actual = String::from_utf8(output).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(expected, actual)
+}
@@ -0,0 +1,45 @@
+use std::fs::read_to_string;
+use std::path::Path;
+use std::string::String;
+
+use jack_compiler::tokenizer::jack_tokenizer::JackTokenizer;
+use jack_compiler::tokenizer::xml_token_writer::XmlTokenWriter;
+
+#[test]
+fn square_main() {
+    let expected_file_path = Path::new("tests/resources/Square/MainT.xml");
+    let src_path = Path::new("tests/resources/Square/Main.jack");
+    test_diff(expected_file_path, src_path);
+}
+
+#[test]
+fn square_square() {
+    let expected_file_path = Path::new("tests/resources/Square/SquareT.xml");
+    let src_path = Path::new("tests/resources/Square/Square.jack");
+    test_diff(expected_file_path, src_path);
+}
+
+#[test]
+fn square_square_game() {
+    let expected_file_path = Path::new("tests/resources/Square/SquareGameT.xml");
+    let src_path = Path::new("tests/resources/Square/SquareGame.jack");
+    test_diff(expected_file_path, src_path);
+}
+
+#[test]
+fn array_test_main() {
+    let expected_file_path = Path::new("tests/resources/ArrayTest/MainT.xml");
+    let src_path = Path::new("tests/resources/ArrayTest/Main.jack");
+    test_diff(expected_file_path, src_path);
+}
+
+fn test_diff(expected_file_path: &Path, src_file_path: &Path) {
+    let expected = read_to_string(expected_file_path).unwrap();
+    let jack_tokenizer = JackTokenizer::new(src_file_path).unwrap();
+    let mut writer = XmlTokenWriter::new(jack_tokenizer);
+    let mut output = Vec::<u8>::new();
+    let result = writer.write_tokens(&mut output);
+    let actual = String::from_utf8(output).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(expected, actual)
+}